@@ -2,21 +2,47 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::WasmMsg::Execute;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
-    StdResult, Uint64,
+    coins, from_binary, from_slice, to_binary, to_vec, Addr, BankMsg, Binary, BlockInfo, Coin,
+    ContractInfoResponse, CosmosMsg, CustomMsg, Deps, DepsMut, Empty, Env, Event, MessageInfo,
+    Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Timestamp, Uint128, Uint64,
+    WasmMsg, WasmQuery,
 };
-use cw2::set_contract_version;
-use cw_storage_plus::Bound;
-use cw_utils::{Duration, Scheduled};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, Item, Map};
+use cw_utils::{maybe_addr, Duration, Scheduled};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::ops::Add;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, OperationListResponse, QueryMsg};
-use crate::state::{Operation, OperationStatus, Timelock, CONFIG, OPERATION_LIST, OPERATION_SEQ};
+use crate::msg::{
+    ConfigHistoryResponse, Cw20BalancesResponse, ExecuteMsg, HookResponse, HooksResponse,
+    InstantiateMsg, MigrateMsg, OperationHookMsg, OperationListResponse, OperationResponse,
+    ProposerUsageResponse, QueryMsg, SimulateOperationResponse, SudoMsg, VestingAssetMsg,
+    VestingStreamListResponse, VestingStreamResponse,
+};
+use crate::state::{
+    ConfigChange, ExpeditePolicy, HookFailurePolicy, InputLimits, Operation, OperationMetadata,
+    OperationStatus, PayloadKind, ProposerLimits, ProposerUsage, Recurrence, Timelock,
+    VestingAsset, VestingStream, ADMINS, CONFIG, CONFIG_HISTORY, CONFIG_HISTORY_SEQ, CW20_BALANCES,
+    EXPEDITE_APPROVALS, GUARDIANS, HOOKS, OPERATION_HISTORY, OPERATION_LIST, OPERATION_SEQ,
+    OPERATION_TAGS, PROPOSERS, PROPOSER_USAGE, REVIVAL_APPROVALS, VESTING_RESERVED, VESTING_SEQ,
+    VESTING_STREAMS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw3-timelock";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// the version storing the roles in the config, which migrate moves out
+const PREVIOUS_VERSION: &str = "0.1.0";
+
+// reply id of hook messages sent with HookFailurePolicy::Ignore
+const HOOK_REPLY_ID: u64 = 1;
+
+// maximum number of urls and of tags in the metadata of an operation
+const MAX_METADATA_ENTRIES: usize = 10;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -45,36 +71,73 @@ pub fn instantiate(
 
     let timelock = Timelock {
         min_time_delay: msg.min_delay,
-        proposers,
-        admins,
         frozen: false,
+        limits: msg.limits.unwrap_or_default(),
+        proposer_limits: ProposerLimits::default(),
+        expedite_policy: None,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     OPERATION_SEQ.save(deps.storage, &Uint64::zero())?;
     CONFIG.save(deps.storage, &timelock)?;
+    for admin in &admins {
+        ADMINS.save(deps.storage, admin, &Empty {})?;
+    }
+    for proposer in &proposers {
+        PROPOSERS.save(deps.storage, proposer, &Empty {})?;
+    }
 
-    Ok(Response::new()
-        .add_attribute("Method: ", "instantiate")
-        .add_attribute("Admin: ", info.sender)
-        .add_attribute(
-            "Proposers: ",
-            timelock
-                .proposers
-                .into_iter()
-                .map(|item| item.to_string())
-                .collect::<String>(),
-        )
-        .add_attribute("minTimeDelay: ", timelock.min_time_delay.to_string()))
+    Ok(Response::new().add_event(duration_attribute(
+        timelock_event("instantiate")
+            .add_attribute("sender", info.sender)
+            .add_attribute("admins", join_addrs(&admins))
+            .add_attribute("proposers", join_addrs(&proposers)),
+        "min_delay",
+        &timelock.min_time_delay,
+    )))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {
+    execute_custom(deps, env, info, msg)
+}
+
+/// Library entry point for chains that schedule their own custom messages:
+/// a contract built with `C = JunoMsg` can export this as its `execute` entry point
+pub fn execute_custom<C>(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg<C>,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg + DeserializeOwned,
+{
+    let res = match msg {
+        ExecuteMsg::ScheduleMsg {
+            msg,
+            title,
+            description,
+            execution_time,
+            executors,
+            recurrence,
+            metadata,
+        } => execute_schedule_msg(
+            deps,
+            _env,
+            info,
+            msg,
+            title,
+            description,
+            execution_time,
+            executors,
+            recurrence,
+            metadata,
+        ),
         ExecuteMsg::Schedule {
             target_address,
             data,
@@ -82,6 +145,8 @@ pub fn execute(
             description,
             execution_time,
             executors,
+            recurrence,
+            metadata,
         } => execute_schedule(
             deps,
             _env,
@@ -92,8 +157,28 @@ pub fn execute(
             description,
             execution_time,
             executors,
+            recurrence,
+            metadata,
+        ),
+        ExecuteMsg::Amend {
+            operation_id,
+            data,
+            title,
+            description,
+            execution_time,
+        } => execute_amend::<C>(
+            deps,
+            _env,
+            info,
+            operation_id,
+            data,
+            title,
+            description,
+            execution_time,
         ),
-        ExecuteMsg::Execute { operation_id } => execute_execute(deps, _env, info, operation_id),
+        ExecuteMsg::Execute { operation_id } => {
+            return execute_execute(deps, _env, info, operation_id)
+        }
         ExecuteMsg::Cancel { operation_id } => execute_cancel(deps, _env, info, operation_id),
         ExecuteMsg::RevokeAdmin { admin_address } => {
             execute_revoke_admin(deps, _env, info, admin_address)
@@ -107,8 +192,146 @@ pub fn execute(
         ExecuteMsg::UpdateMinDelay { new_delay } => {
             execute_update_min_delay(deps, _env, info, new_delay)
         }
+        ExecuteMsg::UpdateInputLimits { limits } => {
+            execute_update_input_limits(deps, _env, info, limits)
+        }
+        ExecuteMsg::AddGuardian { guardian_address } => {
+            execute_add_guardian(deps, _env, info, guardian_address)
+        }
+        ExecuteMsg::RemoveGuardian { guardian_address } => {
+            execute_remove_guardian(deps, _env, info, guardian_address)
+        }
+        ExecuteMsg::Veto { operation_id } => execute_veto(deps, _env, info, operation_id),
+        ExecuteMsg::ApproveRevival { operation_id } => {
+            execute_approve_revival(deps, _env, info, operation_id)
+        }
+        ExecuteMsg::ApproveExpedite { operation_id } => {
+            execute_approve_expedite(deps, _env, info, operation_id)
+        }
+        ExecuteMsg::UpdateExpeditePolicy { policy } => {
+            execute_update_expedite_policy(deps, _env, info, policy)
+        }
+        ExecuteMsg::UpdateProposerLimits { limits } => {
+            execute_update_proposer_limits(deps, _env, info, limits)
+        }
         ExecuteMsg::Freeze {} => execute_freeze(deps, _env, info),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, _env, info, msg),
+        ExecuteMsg::ScheduleCw20Transfer {
+            token_address,
+            recipient,
+            amount,
+            title,
+            description,
+            execution_time,
+            executors,
+        } => execute_schedule_cw20_transfer(
+            deps,
+            _env,
+            info,
+            token_address,
+            recipient,
+            amount,
+            title,
+            description,
+            execution_time,
+            executors,
+        ),
+        ExecuteMsg::ScheduleCw20Send {
+            token_address,
+            contract,
+            amount,
+            msg,
+            title,
+            description,
+            execution_time,
+            executors,
+        } => execute_schedule_cw20_send(
+            deps,
+            _env,
+            info,
+            token_address,
+            contract,
+            amount,
+            msg,
+            title,
+            description,
+            execution_time,
+            executors,
+        ),
+        ExecuteMsg::CreateVestingStream {
+            recipient,
+            asset,
+            total,
+            start,
+            cliff,
+            end,
+        } => execute_create_vesting_stream(
+            deps, _env, info, recipient, asset, total, start, cliff, end,
+        ),
+        ExecuteMsg::ClaimVesting { stream_id } => {
+            execute_claim_vesting(deps, _env, info, stream_id)
+        }
+        ExecuteMsg::AddHook { addr, on_failure } => {
+            execute_add_hook(deps, _env, info, addr, on_failure)
+        }
+        ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, _env, info, addr),
+    }?;
+    Ok(into_custom_response(res)?)
+}
+
+/// every handler emits a `wasm-timelock` event starting with the action
+fn timelock_event(action: &str) -> Event {
+    Event::new("timelock").add_attribute("action", action)
+}
+
+/// timelock event describing the operation as it is after the action
+fn operation_event(action: &str, operation: &Operation) -> Event {
+    let event = timelock_event(action)
+        .add_attribute("operation_id", operation.id)
+        .add_attribute("proposer", &operation.proposer)
+        .add_attribute("target", &operation.target)
+        .add_attribute("status", operation.status.to_string());
+    match operation.execution_time {
+        Scheduled::AtHeight(height) => event.add_attribute("execution_height", height.to_string()),
+        Scheduled::AtTime(time) => event.add_attribute("execution_time", time.nanos().to_string()),
+    }
+}
+
+/// adds a duration as `<key>_seconds` or `<key>_blocks`
+fn duration_attribute(event: Event, key: &str, duration: &Duration) -> Event {
+    match duration {
+        Duration::Height(blocks) => {
+            event.add_attribute(format!("{}_blocks", key), blocks.to_string())
+        }
+        Duration::Time(seconds) => {
+            event.add_attribute(format!("{}_seconds", key), seconds.to_string())
+        }
+    }
+}
+
+fn join_addrs(addrs: &[Addr]) -> String {
+    addrs
+        .iter()
+        .map(|addr| addr.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// handlers other than execute_execute never carry custom messages,
+/// so their responses convert to any custom type through their JSON representation
+fn into_custom_response<C>(res: Response) -> StdResult<Response<C>>
+where
+    C: CustomMsg + DeserializeOwned,
+{
+    let messages: Vec<SubMsg<C>> = from_slice(&to_vec(&res.messages)?)?;
+    let mut custom = Response::new()
+        .add_submessages(messages)
+        .add_attributes(res.attributes)
+        .add_events(res.events);
+    if let Some(data) = res.data {
+        custom = custom.set_data(data);
     }
+    Ok(custom)
 }
 
 /*eslint too-many-arguments-threshold:9 */
@@ -123,19 +346,120 @@ pub fn execute_schedule(
     description: String,
     execution_time: Scheduled,
     executor_list: Option<Vec<String>>,
+    recurrence: Option<Recurrence>,
+    metadata: Option<OperationMetadata>,
 ) -> Result<Response, ContractError> {
-    let sender = deps.api.addr_validate(&info.sender.to_string())?;
     let target = deps.api.addr_validate(&target_address)?;
+    schedule_operation(
+        deps,
+        env,
+        info,
+        target,
+        data,
+        PayloadKind::WasmExecute,
+        title,
+        description,
+        execution_time,
+        executor_list,
+        recurrence,
+        metadata,
+    )
+}
 
-    let timelock = CONFIG.load(deps.storage)?;
-    if !(timelock.proposers.contains(&sender)) {
+#[allow(clippy::too_many_arguments)]
+pub fn execute_schedule_msg<C: CustomMsg>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CosmosMsg<C>,
+    title: String,
+    description: String,
+    execution_time: Scheduled,
+    executor_list: Option<Vec<String>>,
+    recurrence: Option<Recurrence>,
+    metadata: Option<OperationMetadata>,
+) -> Result<Response, ContractError> {
+    // the timelock itself dispatches the message
+    let target = env.contract.address.clone();
+    let data = to_binary(&msg)?;
+    schedule_operation(
+        deps,
+        env,
+        info,
+        target,
+        data,
+        PayloadKind::CosmosMsg,
+        title,
+        description,
+        execution_time,
+        executor_list,
+        recurrence,
+        metadata,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn schedule_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target: Addr,
+    data: Binary,
+    payload_kind: PayloadKind,
+    title: String,
+    description: String,
+    execution_time: Scheduled,
+    executor_list: Option<Vec<String>>,
+    recurrence: Option<Recurrence>,
+    metadata: Option<OperationMetadata>,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_validate(&info.sender.to_string())?;
+
+    if !PROPOSERS.has(deps.storage, &sender) {
         return Err(ContractError::Unauthorized {});
     }
 
+    let timelock = CONFIG.load(deps.storage)?;
     if Scheduled::AtTime(env.block.time).add(timelock.min_time_delay)? > execution_time {
         return Err(ContractError::MinDelayNotSatisfied {});
     }
 
+    check_input_limits(&timelock.limits, &title, &description, &data)?;
+    if let Some(metadata) = &metadata {
+        validate_metadata(&timelock.limits, metadata)?;
+    }
+
+    if let Some(recurrence) = &recurrence {
+        validate_recurrence(recurrence, &execution_time)?;
+    }
+
+    //cw20 transfers out of the treasury must be covered by the tracked balance
+    if let Some((token, amount)) =
+        tracked_cw20_outflow(deps.storage, &payload_kind, &target, &data)?
+    {
+        check_cw20_balance(deps.storage, &token, amount)?;
+    }
+    //native funds the payload sends must not dip into funds reserved for vesting
+    check_native_outflow(deps.as_ref(), &env, &payload_kind, &data)?;
+
+    let mut usage = current_usage(deps.storage, &env, &timelock.proposer_limits, &sender)?;
+    if let Some(max) = timelock.proposer_limits.max_pending {
+        if usage.pending >= max {
+            return Err(ContractError::TooManyPendingOperations { max });
+        }
+    }
+    if let Some(max) = timelock.proposer_limits.max_per_window {
+        if usage.scheduled_in_window >= max {
+            return Err(ContractError::ScheduleRateLimited {
+                max,
+                window_seconds: timelock.proposer_limits.window_seconds,
+            });
+        }
+    }
+    usage.pending += 1;
+    usage.scheduled_in_window += 1;
+    PROPOSER_USAGE.save(deps.storage, &sender, &usage)?;
+
     let id = OPERATION_SEQ.update::<_, StdError>(deps.storage, |id| Ok(id.add(Uint64::new(1))))?;
 
     let mut executors = None;
@@ -158,25 +482,141 @@ pub fn execute_schedule(
         execution_time,
         target,
         data,
+        payload_kind,
         title,
         description,
+        revision: 0,
+        recurrence,
+        execution_count: 0,
+        metadata,
+        expedited: false,
     };
     OPERATION_LIST.save(deps.storage, id.u64(), &new_operation)?;
+    if let Some(metadata) = &new_operation.metadata {
+        for tag in &metadata.tags {
+            OPERATION_TAGS.save(deps.storage, (tag, id.u64()), &Empty {})?;
+        }
+    }
+
+    let hooks = hook_messages(
+        deps.storage,
+        OperationHookMsg::Scheduled {
+            operation_id: id,
+            proposer: new_operation.proposer.clone(),
+            target: new_operation.target.clone(),
+            execution_time: new_operation.execution_time,
+        },
+    )?;
 
     Ok(Response::new()
-        .add_attribute("Schedule ", "success")
-        .add_attribute("Operation ID: ", id)
-        .add_attribute("Proposer: ", new_operation.proposer)
-        .add_attribute("Target Address: ", new_operation.target.to_string())
-        .add_attribute("Execution Time: ", new_operation.execution_time.to_string()))
+        .add_submessages(hooks)
+        .add_event(operation_event("schedule", &new_operation)))
 }
 
-pub fn execute_execute(
+#[allow(clippy::too_many_arguments)]
+pub fn execute_amend<C>(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     operation_id: Uint64,
-) -> Result<Response, ContractError> {
+    data: Option<Binary>,
+    title: Option<String>,
+    description: Option<String>,
+    execution_time: Option<Scheduled>,
+) -> Result<Response, ContractError>
+where
+    C: CustomMsg + DeserializeOwned,
+{
+    let timelock = CONFIG.load(deps.storage)?;
+    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+
+    if operation.proposer != info.sender && !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if operation.status == OperationStatus::Done {
+        return Err(ContractError::Executed {});
+    }
+    // admins approve the revival of the vetoed payload, it can not change under them
+    if operation.status == OperationStatus::Vetoed {
+        return Err(ContractError::Vetoed {});
+    }
+
+    let previous = operation.clone();
+    let earliest = Scheduled::AtTime(env.block.time).add(timelock.min_time_delay)?;
+    if let Some(execution_time) = execution_time {
+        if earliest > execution_time {
+            return Err(ContractError::MinDelayNotSatisfied {});
+        }
+        operation.execution_time = execution_time;
+    }
+
+    //payload change restarts the delay, approvals were given for the old payload
+    if let Some(data) = data {
+        if data != operation.data {
+            //the new payload must pass the checks it would pass if it was scheduled
+            if operation.payload_kind == PayloadKind::CosmosMsg {
+                from_binary::<CosmosMsg<C>>(&data)?;
+            }
+            if let Some((token, amount)) = tracked_cw20_outflow(
+                deps.storage,
+                &operation.payload_kind,
+                &operation.target,
+                &data,
+            )? {
+                check_cw20_balance(deps.storage, &token, amount)?;
+            }
+            check_native_outflow(deps.as_ref(), &env, &operation.payload_kind, &data)?;
+            operation.data = data;
+            if earliest > operation.execution_time {
+                operation.execution_time = earliest;
+            }
+            operation.expedited = false;
+            clear_approvals(deps.storage, EXPEDITE_APPROVALS, operation_id.u64())?;
+            clear_approvals(deps.storage, REVIVAL_APPROVALS, operation_id.u64())?;
+        }
+    }
+    if let Some(title) = title {
+        operation.title = title;
+    }
+    if let Some(description) = description {
+        operation.description = description;
+    }
+    check_input_limits(
+        &timelock.limits,
+        &operation.title,
+        &operation.description,
+        &operation.data,
+    )?;
+    if let Some(recurrence) = &operation.recurrence {
+        validate_recurrence(recurrence, &operation.execution_time)?;
+    }
+
+    // keep the replaced version as history
+    OPERATION_HISTORY.save(
+        deps.storage,
+        (operation_id.u64(), previous.revision),
+        &previous,
+    )?;
+    operation.revision += 1;
+    OPERATION_LIST.save(deps.storage, operation_id.u64(), &operation)?;
+
+    Ok(Response::new().add_event(
+        operation_event("amend", &operation)
+            .add_attribute("sender", info.sender)
+            .add_attribute("revision", operation.revision.to_string()),
+    ))
+}
+
+pub fn execute_execute<C>(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operation_id: Uint64,
+) -> Result<Response<C>, ContractError>
+where
+    C: CustomMsg + DeserializeOwned,
+{
     let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
 
     //is delay ended
@@ -197,18 +637,285 @@ pub fn execute_execute(
     if operation.status == OperationStatus::Done {
         return Err(ContractError::Executed {});
     }
+    if operation.status == OperationStatus::Vetoed {
+        return Err(ContractError::Vetoed {});
+    }
 
-    //change operation status
+    if let Some((token, amount)) = tracked_cw20_outflow(
+        deps.storage,
+        &operation.payload_kind,
+        &operation.target,
+        &operation.data,
+    )? {
+        let balance = check_cw20_balance(deps.storage, &token, amount)?;
+        CW20_BALANCES.save(deps.storage, &token, &(balance - amount))?;
+    }
+    check_native_outflow(
+        deps.as_ref(),
+        &env,
+        &operation.payload_kind,
+        &operation.data,
+    )?;
+
+    //change operation status, recurring operations are rescheduled until they end
+    operation.execution_count += 1;
     operation.status = OperationStatus::Done;
+    if let Some(recurrence) = &operation.recurrence {
+        if let Some(next) = next_execution_time(recurrence, &operation, &env.block)? {
+            operation.execution_time = next;
+            operation.status = OperationStatus::Pending;
+        }
+    }
     OPERATION_LIST.save(deps.storage, operation_id.u64(), &operation)?;
+    if operation.status == OperationStatus::Done {
+        release_pending(deps.storage, &operation.proposer)?;
+    }
 
-    Ok(Response::new()
-        .add_message(CosmosMsg::Wasm(Execute {
+    let hooks = hook_messages(
+        deps.storage,
+        OperationHookMsg::Executed {
+            operation_id,
+            executor: info.sender.clone(),
+        },
+    )?;
+    let event = operation_event("execute", &operation)
+        .add_attribute("executor", &info.sender)
+        .add_attribute("execution_count", operation.execution_count.to_string())
+        .add_attribute("expedited", operation.expedited.to_string());
+
+    let msg: CosmosMsg<C> = match operation.payload_kind {
+        PayloadKind::WasmExecute => CosmosMsg::Wasm(Execute {
             contract_addr: operation.target.to_string(),
             msg: operation.data,
             funds: vec![],
-        }))
-        .add_attribute("executor", &info.sender.to_string()))
+        }),
+        PayloadKind::CosmosMsg => from_binary(&operation.data)?,
+    };
+    let hooks: Vec<SubMsg<C>> = from_slice(&to_vec(&hooks)?)?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_submessages(hooks)
+        .add_event(event))
+}
+
+/// builds the messages notifying every subscriber, failures of ignored hooks are caught in reply
+fn hook_messages(storage: &dyn Storage, msg: OperationHookMsg) -> StdResult<Vec<SubMsg>> {
+    HOOKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (addr, on_failure) = item?;
+            let cosmos_msg = msg.clone().into_cosmos_msg(addr)?;
+            Ok(match on_failure {
+                HookFailurePolicy::Ignore => SubMsg::reply_on_error(cosmos_msg, HOOK_REPLY_ID),
+                HookFailurePolicy::Revert => SubMsg::new(cosmos_msg),
+            })
+        })
+        .collect()
+}
+
+/// returns the token and the amount a cw20 message sends out of the timelock, if it calls a
+/// tracked token as the target of the operation or as the contract of a scheduled CosmosMsg
+fn tracked_cw20_outflow(
+    storage: &dyn Storage,
+    payload_kind: &PayloadKind,
+    target: &Addr,
+    data: &Binary,
+) -> StdResult<Option<(Addr, Uint128)>> {
+    let (token, msg) = match payload_kind {
+        PayloadKind::WasmExecute => (target.clone(), data.clone()),
+        PayloadKind::CosmosMsg => match from_binary::<CosmosMsg>(data) {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            })) => (Addr::unchecked(contract_addr), msg),
+            _ => return Ok(None),
+        },
+    };
+    if !CW20_BALANCES.has(storage, &token) {
+        return Ok(None);
+    }
+    match from_binary(&msg) {
+        Ok(Cw20ExecuteMsg::Transfer { amount, .. })
+        | Ok(Cw20ExecuteMsg::Send { amount, .. })
+        | Ok(Cw20ExecuteMsg::Burn { amount }) => Ok(Some((token, amount))),
+        _ => Ok(None),
+    }
+}
+
+/// returns the tracked balance of the token if it covers the amount
+fn check_cw20_balance(
+    storage: &dyn Storage,
+    token: &Addr,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let balance = CW20_BALANCES.may_load(storage, token)?.unwrap_or_default();
+    if amount > balance {
+        return Err(ContractError::InsufficientCw20Balance {
+            token: token.to_string(),
+            balance,
+            amount,
+        });
+    }
+    Ok(balance)
+}
+
+/// returns the native funds a scheduled CosmosMsg sends out of the timelock
+fn native_outflow(payload_kind: &PayloadKind, data: &Binary) -> Vec<Coin> {
+    match payload_kind {
+        PayloadKind::WasmExecute => vec![],
+        PayloadKind::CosmosMsg => match from_binary::<CosmosMsg>(data) {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute { funds, .. })) => funds,
+            Ok(CosmosMsg::Bank(BankMsg::Send { amount, .. })) => amount,
+            _ => vec![],
+        },
+    }
+}
+
+/// native balance of the timelock that is not reserved by vesting streams
+fn available_native_balance(deps: Deps, env: &Env, denom: &str) -> StdResult<Uint128> {
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, denom)?
+        .amount;
+    let reserved = VESTING_RESERVED
+        .may_load(deps.storage, denom)?
+        .unwrap_or_default();
+    Ok(balance.saturating_sub(reserved))
+}
+
+fn check_native_outflow(
+    deps: Deps,
+    env: &Env,
+    payload_kind: &PayloadKind,
+    data: &Binary,
+) -> Result<(), ContractError> {
+    for coin in native_outflow(payload_kind, data) {
+        let available = available_native_balance(deps, env, &coin.denom)?;
+        if available < coin.amount {
+            return Err(ContractError::InsufficientNativeBalance {
+                denom: coin.denom,
+                available,
+                amount: coin.amount,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_input_limits(
+    limits: &InputLimits,
+    title: &str,
+    description: &str,
+    data: &Binary,
+) -> Result<(), ContractError> {
+    let max = limits.max_title_length as usize;
+    if title.len() > max {
+        return Err(ContractError::TitleTooLong {
+            length: title.len(),
+            max,
+        });
+    }
+    let max = limits.max_description_length as usize;
+    if description.len() > max {
+        return Err(ContractError::DescriptionTooLong {
+            length: description.len(),
+            max,
+        });
+    }
+    let max = limits.max_data_length as usize;
+    if data.len() > max {
+        return Err(ContractError::PayloadTooLarge {
+            length: data.len(),
+            max,
+        });
+    }
+    Ok(())
+}
+
+fn validate_metadata(
+    limits: &InputLimits,
+    metadata: &OperationMetadata,
+) -> Result<(), ContractError> {
+    if let Some(hash) = &metadata.content_hash {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ContractError::InvalidContentHash {});
+        }
+    }
+    for list in [&metadata.urls, &metadata.tags] {
+        let mut sorted: Vec<&String> = list.iter().collect();
+        sorted.sort();
+        sorted.dedup();
+        if list.len() > MAX_METADATA_ENTRIES
+            || sorted.len() != list.len()
+            || list.iter().any(|entry| entry.is_empty())
+        {
+            return Err(ContractError::InvalidMetadata {
+                max: MAX_METADATA_ENTRIES,
+            });
+        }
+        let max = limits.max_metadata_entry_length as usize;
+        if let Some(entry) = list.iter().find(|entry| entry.len() > max) {
+            return Err(ContractError::MetadataEntryTooLong {
+                length: entry.len(),
+                max,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn validate_recurrence(
+    recurrence: &Recurrence,
+    execution_time: &Scheduled,
+) -> Result<(), ContractError> {
+    if recurrence.interval == Duration::Time(0) || recurrence.interval == Duration::Height(0) {
+        return Err(ContractError::InvalidRecurrence {});
+    }
+    execution_time
+        .add(recurrence.interval)
+        .map_err(|_| ContractError::InvalidRecurrence {})?;
+    if let Some(end) = recurrence.end {
+        if end.partial_cmp(execution_time).is_none() {
+            return Err(ContractError::InvalidRecurrence {});
+        }
+    }
+    Ok(())
+}
+
+/// returns the next trigger time of a recurring operation that has just been executed,
+/// or None if the recurrence has ended
+/// the next period after the current block, a late execution skips the periods it missed
+/// instead of making them executable back to back
+fn next_execution_time(
+    recurrence: &Recurrence,
+    operation: &Operation,
+    block: &BlockInfo,
+) -> StdResult<Option<Scheduled>> {
+    if let Some(max_executions) = recurrence.max_executions {
+        if operation.execution_count >= max_executions {
+            return Ok(None);
+        }
+    }
+    let mut next = operation.execution_time.add(recurrence.interval)?;
+    if next.is_triggered(block) {
+        next = match (next, recurrence.interval) {
+            (Scheduled::AtHeight(height), Duration::Height(interval)) => {
+                let missed = (block.height - height) / interval + 1;
+                Scheduled::AtHeight(height + missed * interval)
+            }
+            (Scheduled::AtTime(time), Duration::Time(interval)) => {
+                let missed = (block.time.seconds() - time.seconds()) / interval + 1;
+                Scheduled::AtTime(time.plus_seconds(missed * interval))
+            }
+            _ => next,
+        };
+    }
+    if let Some(end) = recurrence.end {
+        if next > end {
+            return Ok(None);
+        }
+    }
+    Ok(Some(next))
 }
 
 pub fn execute_cancel(
@@ -217,7 +924,7 @@ pub fn execute_cancel(
     info: MessageInfo,
     operation_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
 
     if operation.status == OperationStatus::Done {
         return Err(ContractError::NotDeletable {});
@@ -227,13 +934,60 @@ pub fn execute_cancel(
         return Err(ContractError::Unauthorized {});
     }
 
-    OPERATION_LIST.remove(deps.storage, operation_id.u64());
+    remove_operation(deps.storage, &operation)?;
 
+    let hooks = hook_messages(
+        deps.storage,
+        OperationHookMsg::Cancelled {
+            operation_id,
+            sender: Some(info.sender.clone()),
+        },
+    )?;
+
+    operation.status = OperationStatus::Cancelled;
     Ok(Response::new()
-        .add_attribute("Method", "cancel")
-        .add_attribute("sender", &info.sender.to_string())
-        .add_attribute("operation_id", operation_id.to_string())
-        .add_attribute("Result", "Success"))
+        .add_submessages(hooks)
+        .add_event(operation_event("cancel", &operation).add_attribute("sender", info.sender)))
+}
+
+/// removes a cancelled operation together with its tag index entries
+fn remove_operation(storage: &mut dyn Storage, operation: &Operation) -> StdResult<()> {
+    if let Some(metadata) = &operation.metadata {
+        for tag in &metadata.tags {
+            OPERATION_TAGS.remove(storage, (tag, operation.id.u64()));
+        }
+    }
+    OPERATION_LIST.remove(storage, operation.id.u64());
+    clear_approvals(storage, EXPEDITE_APPROVALS, operation.id.u64())?;
+    clear_approvals(storage, REVIVAL_APPROVALS, operation.id.u64())?;
+    release_pending(storage, &operation.proposer)
+}
+
+/// usage of the proposer, with the rate limit window restarted if it is over
+fn current_usage(
+    storage: &dyn Storage,
+    env: &Env,
+    limits: &ProposerLimits,
+    proposer: &Addr,
+) -> StdResult<ProposerUsage> {
+    let mut usage = PROPOSER_USAGE
+        .may_load(storage, proposer)?
+        .unwrap_or_default();
+    if env.block.time >= usage.window_start.plus_seconds(limits.window_seconds) {
+        usage.window_start = env.block.time;
+        usage.scheduled_in_window = 0;
+    }
+    Ok(usage)
+}
+
+/// frees a pending slot of the proposer once an operation is executed for good or cancelled
+fn release_pending(storage: &mut dyn Storage, proposer: &Addr) -> StdResult<()> {
+    if let Some(mut usage) = PROPOSER_USAGE.may_load(storage, proposer)? {
+        // operations scheduled before usage was tracked have no slot
+        usage.pending = usage.pending.saturating_sub(1);
+        PROPOSER_USAGE.save(storage, proposer, &usage)?;
+    }
+    Ok(())
 }
 
 pub fn execute_revoke_admin(
@@ -242,31 +996,28 @@ pub fn execute_revoke_admin(
     info: MessageInfo,
     admin_address: String,
 ) -> Result<Response, ContractError> {
-    let mut timelock = CONFIG.load(deps.storage)?;
+    let timelock = CONFIG.load(deps.storage)?;
     if timelock.frozen {
         return Err(ContractError::TimelockFrozen {});
     }
-    if !timelock.admins.contains(&info.sender) {
+    if !ADMINS.has(deps.storage, &info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
     let admin_address = deps.api.addr_validate(&admin_address)?;
 
-    let index = timelock
-        .admins
-        .iter()
-        .position(|x| *x == admin_address.clone())
-        .ok_or(ContractError::NotFound {
-            address: admin_address.clone().to_string(),
-        })?;
+    if !ADMINS.has(deps.storage, &admin_address) {
+        return Err(ContractError::NotFound {
+            address: admin_address.to_string(),
+        });
+    }
 
-    timelock.admins.remove(index);
-    CONFIG.save(deps.storage, &timelock)?;
-    Ok(Response::new()
-        .add_attribute("Method", "revoke admin")
-        .add_attribute("sender", &info.sender)
-        .add_attribute("Admin to revoke", admin_address)
-        .add_attribute("Result", "Success"))
+    ADMINS.remove(deps.storage, &admin_address);
+    Ok(Response::new().add_event(
+        timelock_event("revoke_admin")
+            .add_attribute("sender", info.sender)
+            .add_attribute("admin", admin_address),
+    ))
 }
 
 pub fn execute_add_proposer(
@@ -275,29 +1026,29 @@ pub fn execute_add_proposer(
     info: MessageInfo,
     proposer_address: String,
 ) -> Result<Response, ContractError> {
-    let mut timelock = CONFIG.load(deps.storage)?;
+    let timelock = CONFIG.load(deps.storage)?;
 
     if timelock.frozen {
         return Err(ContractError::TimelockFrozen {});
     }
 
-    if !timelock.admins.contains(&info.sender) {
+    if !ADMINS.has(deps.storage, &info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
     let proposer_address = deps.api.addr_validate(&proposer_address)?;
 
     //is in proposers list
-    if timelock.proposers.contains(&proposer_address) {
+    if PROPOSERS.has(deps.storage, &proposer_address) {
         return Err(ContractError::AlreadyContainsProposerAddress {});
     }
 
-    timelock.proposers.push(proposer_address);
-    CONFIG.save(deps.storage, &timelock)?;
-    Ok(Response::new()
-        .add_attribute("Method", "add_proposer")
-        .add_attribute("sender", &info.sender)
-        .add_attribute("Result", "Success"))
+    PROPOSERS.save(deps.storage, &proposer_address, &Empty {})?;
+    Ok(Response::new().add_event(
+        timelock_event("add_proposer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("proposer", proposer_address),
+    ))
 }
 
 pub fn execute_remove_proposer(
@@ -306,558 +1057,3899 @@ pub fn execute_remove_proposer(
     info: MessageInfo,
     proposer_address: String,
 ) -> Result<Response, ContractError> {
-    let mut timelock = CONFIG.load(deps.storage)?;
+    let timelock = CONFIG.load(deps.storage)?;
 
     if timelock.frozen {
         return Err(ContractError::TimelockFrozen {});
     }
 
-    if !timelock.admins.contains(&info.sender) {
+    if !ADMINS.has(deps.storage, &info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
     let proposer_address = deps.api.addr_validate(&proposer_address)?;
     //is in proposers
-    let index = timelock
-        .proposers
-        .iter()
-        .position(|x| *x == proposer_address.clone())
-        .ok_or(ContractError::NotFound {
-            address: proposer_address.clone().to_string(),
-        })?;
+    if !PROPOSERS.has(deps.storage, &proposer_address) {
+        return Err(ContractError::NotFound {
+            address: proposer_address.to_string(),
+        });
+    }
 
-    timelock.proposers.remove(index);
-    CONFIG.save(deps.storage, &timelock)?;
-    Ok(Response::new()
-        .add_attribute("Method", "remove_proposer")
-        .add_attribute("sender", &info.sender)
-        .add_attribute("Result", "Success"))
+    PROPOSERS.remove(deps.storage, &proposer_address);
+    Ok(Response::new().add_event(
+        timelock_event("remove_proposer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("proposer", proposer_address),
+    ))
 }
 
-pub fn execute_update_min_delay(
+pub fn execute_add_guardian(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    new_delay: Duration,
+    guardian_address: String,
 ) -> Result<Response, ContractError> {
-    let mut timelock = CONFIG.load(deps.storage)?;
+    let timelock = CONFIG.load(deps.storage)?;
 
     if timelock.frozen {
         return Err(ContractError::TimelockFrozen {});
     }
 
-    if !timelock.admins.contains(&info.sender) {
+    if !ADMINS.has(deps.storage, &info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
-    timelock.min_time_delay = new_delay;
+    let guardian_address = deps.api.addr_validate(&guardian_address)?;
 
-    CONFIG.save(deps.storage, &timelock)?;
-    Ok(Response::new()
-        .add_attribute("Method", "Update Min Delay")
-        .add_attribute("Sender", &info.sender.to_string())
-        .add_attribute("New Min Delay", timelock.min_time_delay.to_string())
-        .add_attribute("Result", "Success"))
+    if GUARDIANS.has(deps.storage, &guardian_address) {
+        return Err(ContractError::AlreadyContainsGuardianAddress {});
+    }
+
+    GUARDIANS.save(deps.storage, &guardian_address, &Empty {})?;
+    Ok(Response::new().add_event(
+        timelock_event("add_guardian")
+            .add_attribute("sender", info.sender)
+            .add_attribute("guardian", guardian_address),
+    ))
 }
-pub fn execute_freeze(
+
+pub fn execute_remove_guardian(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
+    guardian_address: String,
 ) -> Result<Response, ContractError> {
-    let mut timelock = CONFIG.load(deps.storage)?;
+    let timelock = CONFIG.load(deps.storage)?;
 
     if timelock.frozen {
         return Err(ContractError::TimelockFrozen {});
     }
 
-    if !timelock.admins.contains(&info.sender) {
+    if !ADMINS.has(deps.storage, &info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
-    timelock.frozen = true;
+    let guardian_address = deps.api.addr_validate(&guardian_address)?;
+    if !GUARDIANS.has(deps.storage, &guardian_address) {
+        return Err(ContractError::NotFound {
+            address: guardian_address.to_string(),
+        });
+    }
 
-    CONFIG.save(deps.storage, &timelock)?;
+    GUARDIANS.remove(deps.storage, &guardian_address);
+    Ok(Response::new().add_event(
+        timelock_event("remove_guardian")
+            .add_attribute("sender", info.sender)
+            .add_attribute("guardian", guardian_address),
+    ))
+}
+
+pub fn execute_veto(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operation_id: Uint64,
+) -> Result<Response, ContractError> {
+    if !GUARDIANS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    if operation.status != OperationStatus::Pending
+        || operation.execution_time.is_triggered(&env.block)
+    {
+        return Err(ContractError::VetoWindowClosed {});
+    }
+
+    operation.status = OperationStatus::Vetoed;
+    OPERATION_LIST.save(deps.storage, operation_id.u64(), &operation)?;
+    // a revived operation needs a new quorum to be expedited
+    clear_approvals(deps.storage, EXPEDITE_APPROVALS, operation_id.u64())?;
+
+    let hooks = hook_messages(
+        deps.storage,
+        OperationHookMsg::Vetoed {
+            operation_id,
+            guardian: info.sender.clone(),
+        },
+    )?;
 
     Ok(Response::new()
-        .add_attribute("Method", "freeze")
-        .add_attribute("sender", &info.sender)
-        .add_attribute("Result", "Success"))
+        .add_submessages(hooks)
+        .add_event(operation_event("veto", &operation).add_attribute("guardian", info.sender)))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetOperationStatus { operation_id } => {
-            to_binary(&query_get_operation_status(deps, operation_id)?)
-        }
-        QueryMsg::GetExecutionTime { operation_id } => {
-            to_binary(&query_get_execution_time(deps, operation_id)?)
-        }
-        QueryMsg::GetAdmins {} => to_binary(&query_get_admins(deps)?),
-        QueryMsg::GetOperations { start_after, limit } => {
-            to_binary(&query_get_operations(deps, start_after, limit)?)
-        }
-        QueryMsg::GetMinDelay {} => to_binary(&query_get_min_delay(deps)?),
-        QueryMsg::GetProposers {} => to_binary(&query_get_proposers(deps)?),
-        QueryMsg::GetExecutors { operation_id } => {
-            to_binary(&query_get_executors(deps, operation_id)?)
-        }
+pub fn execute_approve_revival(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operation_id: Uint64,
+) -> Result<Response, ContractError> {
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
     }
-}
 
-pub fn query_get_operation_status(deps: Deps, operation_id: Uint64) -> StdResult<OperationStatus> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
-    Ok(operation.status)
-}
+    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    if operation.status != OperationStatus::Vetoed {
+        return Err(ContractError::NotVetoed {});
+    }
+    if REVIVAL_APPROVALS.has(deps.storage, (operation_id.u64(), &info.sender)) {
+        return Err(ContractError::AlreadyApproved {});
+    }
+    REVIVAL_APPROVALS.save(deps.storage, (operation_id.u64(), &info.sender), &Empty {})?;
 
-pub fn query_get_execution_time(deps: Deps, operation_id: Uint64) -> StdResult<String> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
-    Ok(operation.execution_time.to_string())
+    let approvals = query_get_revival_approvals(deps.as_ref(), operation_id)?.len();
+    let required = admin_supermajority(deps.as_ref(), &env)?;
+    let res = Response::new().add_event(
+        operation_event("approve_revival", &operation)
+            .add_attribute("sender", &info.sender)
+            .add_attribute("approvals", approvals.to_string())
+            .add_attribute("required", required.to_string()),
+    );
+    if approvals < required {
+        return Ok(res);
+    }
+
+    // the revived operation goes through the minimum delay again
+    let timelock = CONFIG.load(deps.storage)?;
+    let earliest = Scheduled::AtTime(env.block.time).add(timelock.min_time_delay)?;
+    if earliest > operation.execution_time {
+        operation.execution_time = earliest;
+    }
+    operation.status = OperationStatus::Pending;
+    OPERATION_LIST.save(deps.storage, operation_id.u64(), &operation)?;
+    clear_approvals(deps.storage, REVIVAL_APPROVALS, operation_id.u64())?;
+
+    let hooks = hook_messages(
+        deps.storage,
+        OperationHookMsg::Revived {
+            operation_id,
+            execution_time: operation.execution_time,
+        },
+    )?;
+
+    Ok(res
+        .add_submessages(hooks)
+        .add_event(operation_event("revive", &operation)))
 }
 
-pub fn query_get_admins(deps: Deps) -> StdResult<Vec<Addr>> {
+pub fn execute_approve_expedite(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operation_id: Uint64,
+) -> Result<Response, ContractError> {
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
     let timelock = CONFIG.load(deps.storage)?;
-    Ok(timelock.admins)
+    let policy = timelock
+        .expedite_policy
+        .ok_or(ContractError::ExpediteDisabled {})?;
+
+    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    if operation.status != OperationStatus::Pending || operation.expedited {
+        return Err(ContractError::NotExpeditable {});
+    }
+    if EXPEDITE_APPROVALS.has(deps.storage, (operation_id.u64(), &info.sender)) {
+        return Err(ContractError::AlreadyApproved {});
+    }
+    EXPEDITE_APPROVALS.save(deps.storage, (operation_id.u64(), &info.sender), &Empty {})?;
+
+    let approvals = query_get_expedite_approvals(deps.as_ref(), operation_id)?.len();
+    let res = Response::new().add_event(
+        operation_event("approve_expedite", &operation)
+            .add_attribute("sender", &info.sender)
+            .add_attribute("approvals", approvals.to_string())
+            .add_attribute("quorum", policy.quorum.to_string()),
+    );
+    if approvals < policy.quorum as usize {
+        return Ok(res);
+    }
+
+    // the expedited delay runs from the moment the quorum is reached
+    let expedited_time = Scheduled::AtTime(env.block.time).add(policy.min_delay)?;
+    // operations scheduled at a height move to the expedited time
+    if operation.execution_time.partial_cmp(&expedited_time) != Some(Ordering::Less) {
+        operation.execution_time = expedited_time;
+    }
+    operation.expedited = true;
+    OPERATION_LIST.save(deps.storage, operation_id.u64(), &operation)?;
+    clear_approvals(deps.storage, EXPEDITE_APPROVALS, operation_id.u64())?;
+
+    Ok(res.add_event(operation_event("expedite", &operation)))
 }
 
-// settings for pagination
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
+pub fn execute_update_expedite_policy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    policy: Option<ExpeditePolicy>,
+) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
 
-pub fn query_get_operations(
-    deps: Deps,
-    start_after: Option<u64>,
-    limit: Option<u32>,
-) -> StdResult<OperationListResponse> {
-    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
-    let operations: StdResult<Vec<_>> = OPERATION_LIST
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .collect();
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
+    }
 
-    let res = OperationListResponse {
-        operationList: operations?.into_iter().map(|l| l.1.into()).collect(),
-    };
-    Ok(res)
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(policy) = &policy {
+        if policy.quorum == 0 || !shorter_or_equal(&policy.min_delay, &timelock.min_time_delay) {
+            return Err(ContractError::InvalidExpeditePolicy {});
+        }
+    }
+
+    timelock.expedite_policy = policy;
+
+    CONFIG.save(deps.storage, &timelock)?;
+    let mut event = timelock_event("update_expedite_policy").add_attribute("sender", info.sender);
+    if let Some(policy) = &timelock.expedite_policy {
+        event = duration_attribute(event, "min_delay", &policy.min_delay)
+            .add_attribute("quorum", policy.quorum.to_string());
+    }
+    Ok(Response::new().add_event(event))
 }
 
-pub fn query_get_min_delay(deps: Deps) -> StdResult<String> {
-    let timelock = CONFIG.load(deps.storage)?;
-    Ok(timelock.min_time_delay.to_string())
+/// durations of different kinds can not be compared
+fn shorter_or_equal(delay: &Duration, max: &Duration) -> bool {
+    match (delay, max) {
+        (Duration::Height(delay), Duration::Height(max)) => delay <= max,
+        (Duration::Time(delay), Duration::Time(max)) => delay <= max,
+        _ => false,
+    }
 }
 
-pub fn query_get_proposers(deps: Deps) -> StdResult<Vec<Addr>> {
-    let timelock = CONFIG.load(deps.storage)?;
-    Ok(timelock.proposers)
+fn clear_approvals(
+    storage: &mut dyn Storage,
+    approvals: Map<(u64, &Addr), Empty>,
+    operation_id: u64,
+) -> StdResult<()> {
+    let approvers: Vec<Addr> = approvals
+        .prefix(operation_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for approver in &approvers {
+        approvals.remove(storage, (operation_id, approver));
+    }
+    Ok(())
 }
 
-pub fn query_get_executors(deps: Deps, operation_id: Uint64) -> StdResult<Vec<Addr>> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
-    Ok(operation.executors.unwrap_or_default())
+/// approvers of the operation that are still admins, approvals of revoked admins do not count
+fn admin_approvals(
+    storage: &dyn Storage,
+    approvals: Map<(u64, &Addr), Empty>,
+    operation_id: u64,
+) -> StdResult<Vec<Addr>> {
+    approvals
+        .prefix(operation_id)
+        .keys(storage, None, None, Order::Ascending)
+        .filter(|approver| !matches!(approver, Ok(approver) if !ADMINS.has(storage, approver)))
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::Timestamp;
-    use cw_utils::Scheduled;
+/// two thirds of the admins, not counting the timelock itself which only acts through operations
+fn admin_supermajority(deps: Deps, env: &Env) -> StdResult<usize> {
+    let admins = ADMINS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .filter(|admin| !matches!(admin, Ok(admin) if *admin == env.contract.address))
+        .count();
+    // ceil(2n / 3)
+    Ok(admins - admins / 3)
+}
 
-    #[test]
-    fn test_no_executers() {
-        let mut deps = mock_dependencies();
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
-        let msg = InstantiateMsg {
-            admins: Option::Some(vec!["owner".to_string(), "new_one".to_string()]),
-            proposers: vec!["prop1".to_string(), "prop2".to_string()],
-            min_delay: Duration::Time(10),
-        };
-        let info = mock_info("creator", &[]);
-        let description = "test desc".to_string();
-        let title = "Title Example ".to_string();
-        // instantiate
-        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        println!("{:?}", res);
+pub fn execute_update_min_delay(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_delay: Duration,
+) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
 
-        let data = to_binary(&"data").unwrap();
-        // try Schedule() with sender "creator"
-        let res = execute_schedule(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(10)),
-            Option::None,
-        )
-        .unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
+    }
 
-        //change sender to prop1
-        let info = mock_info("prop1", &[]);
-        //try Schedule() sender "prop1" execution_time < env.block.time
-        let res = execute_schedule(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(1)),
-            Option::None,
-        )
-        .unwrap_err();
-        assert_eq!(res, ContractError::MinDelayNotSatisfied {});
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
 
-        //Schedule() sender "prop1" execution_time > env.block.time && min_delay_time > execution_time - env.block.time
-        let res = execute_schedule(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(120)),
-            Option::None,
-        )
-        .unwrap();
-        println!("{:?}", res);
+    timelock.min_time_delay = new_delay;
 
-        let res = query_get_execution_time(deps.as_ref(), Uint64::new(1));
-        println!("{:?}, {}", res, env.block.time);
+    CONFIG.save(deps.storage, &timelock)?;
+    Ok(Response::new().add_event(duration_attribute(
+        timelock_event("update_min_delay").add_attribute("sender", info.sender),
+        "min_delay",
+        &timelock.min_time_delay,
+    )))
+}
 
-        //try Execute() sender "prop1" execution_time > env.block.time
-        let res =
-            execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap_err();
-        assert_eq!(res, ContractError::Unexpired {});
+pub fn execute_update_input_limits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    limits: InputLimits,
+) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
 
-        //time pass
-        env.block.time = Timestamp::from_seconds(120);
-        //try Execute() sender "prop1" execution_time <= env.block.time executors "none"
-        let res =
-            execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
-        println!("{:?}", res);
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
     }
 
-    #[test]
-    fn test_with_executors() {
-        let mut deps = mock_dependencies();
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
-        let msg = InstantiateMsg {
-            admins: Option::Some(vec!["owner".to_string(), "newone".to_string()]),
-            proposers: vec!["prop1".to_string(), "prop2".to_string()],
-            min_delay: Duration::Time(10),
-        };
-        let info = mock_info("creator", &[]);
-        let title = "Title Example ".to_string();
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
 
-        // instantiate
-        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        println!("{:?}", res);
+    timelock.limits = limits;
 
-        let data = to_binary(&"data").unwrap();
+    CONFIG.save(deps.storage, &timelock)?;
+    Ok(Response::new().add_event(
+        timelock_event("update_input_limits")
+            .add_attribute("sender", info.sender)
+            .add_attribute(
+                "max_title_length",
+                timelock.limits.max_title_length.to_string(),
+            )
+            .add_attribute(
+                "max_description_length",
+                timelock.limits.max_description_length.to_string(),
+            )
+            .add_attribute(
+                "max_data_length",
+                timelock.limits.max_data_length.to_string(),
+            ),
+    ))
+}
+
+pub fn execute_update_proposer_limits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    limits: ProposerLimits,
+) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
+
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
+    }
+
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if limits.max_per_window.is_some() && limits.window_seconds == 0 {
+        return Err(ContractError::InvalidProposerLimits {});
+    }
+
+    timelock.proposer_limits = limits;
+
+    CONFIG.save(deps.storage, &timelock)?;
+    let limits = timelock.proposer_limits;
+    let mut event = timelock_event("update_proposer_limits").add_attribute("sender", info.sender);
+    if let Some(max_pending) = limits.max_pending {
+        event = event.add_attribute("max_pending", max_pending.to_string());
+    }
+    if let Some(max_per_window) = limits.max_per_window {
+        event = event
+            .add_attribute("max_per_window", max_per_window.to_string())
+            .add_attribute("window_seconds", limits.window_seconds.to_string());
+    }
+    Ok(Response::new().add_event(event))
+}
+
+pub fn execute_freeze(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
+
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
+    }
+
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    timelock.frozen = true;
+
+    CONFIG.save(deps.storage, &timelock)?;
+
+    Ok(Response::new().add_event(timelock_event("freeze").add_attribute("sender", info.sender)))
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // info.sender is the cw20 token contract
+    let balance = CW20_BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(wrapper.amount)?)
+    })?;
+
+    Ok(Response::new().add_event(
+        timelock_event("receive")
+            .add_attribute("token", info.sender)
+            .add_attribute("sender", wrapper.sender)
+            .add_attribute("amount", wrapper.amount)
+            .add_attribute("balance", balance),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_schedule_cw20_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_address: String,
+    recipient: String,
+    amount: Uint128,
+    title: String,
+    description: String,
+    execution_time: Scheduled,
+    executors: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let token = deps.api.addr_validate(&token_address)?;
+    deps.api.addr_validate(&recipient)?;
+    check_cw20_balance(deps.storage, &token, amount)?;
+
+    let data = to_binary(&Cw20ExecuteMsg::Transfer { recipient, amount })?;
+    execute_schedule(
+        deps,
+        env,
+        info,
+        token_address,
+        data,
+        title,
+        description,
+        execution_time,
+        executors,
+        None,
+        None,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_schedule_cw20_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_address: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+    title: String,
+    description: String,
+    execution_time: Scheduled,
+    executors: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let token = deps.api.addr_validate(&token_address)?;
+    deps.api.addr_validate(&contract)?;
+    check_cw20_balance(deps.storage, &token, amount)?;
+
+    let data = to_binary(&Cw20ExecuteMsg::Send {
+        contract,
+        amount,
+        msg,
+    })?;
+    execute_schedule(
+        deps,
+        env,
+        info,
+        token_address,
+        data,
+        title,
+        description,
+        execution_time,
+        executors,
+        None,
+        None,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_vesting_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    asset: VestingAssetMsg,
+    total: Uint128,
+    start: Timestamp,
+    cliff: Timestamp,
+    end: Timestamp,
+) -> Result<Response, ContractError> {
+    // streams are only created by executing a timelocked operation
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if total.is_zero() || start > cliff || cliff > end || start >= end {
+        return Err(ContractError::InvalidVestingSchedule {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    //reserve the streamed amount
+    let asset = match asset {
+        VestingAssetMsg::Native { denom } => {
+            let reserved = VESTING_RESERVED
+                .may_load(deps.storage, &denom)?
+                .unwrap_or_default();
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &denom)?
+                .amount;
+            if balance < reserved.checked_add(total).map_err(StdError::from)? {
+                return Err(ContractError::InsufficientBalance { denom });
+            }
+            VESTING_RESERVED.save(deps.storage, &denom, &(reserved + total))?;
+            VestingAsset::Native { denom }
+        }
+        VestingAssetMsg::Cw20 { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let balance = check_cw20_balance(deps.storage, &address, total)?;
+            CW20_BALANCES.save(deps.storage, &address, &(balance - total))?;
+            VestingAsset::Cw20 { address }
+        }
+    };
+
+    let id = VESTING_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    VESTING_SEQ.save(deps.storage, &id)?;
+    let stream = VestingStream {
+        id,
+        recipient,
+        asset,
+        total,
+        claimed: Uint128::zero(),
+        start,
+        cliff,
+        end,
+    };
+    VESTING_STREAMS.save(deps.storage, id, &stream)?;
+
+    Ok(Response::new().add_event(
+        timelock_event("create_vesting_stream")
+            .add_attribute("stream_id", id.to_string())
+            .add_attribute("recipient", stream.recipient)
+            .add_attribute("total", total),
+    ))
+}
+
+pub fn execute_claim_vesting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stream_id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = VESTING_STREAMS.load(deps.storage, stream_id)?;
+    if stream.recipient != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let amount = stream.vested(env.block.time) - stream.claimed;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    stream.claimed += amount;
+    VESTING_STREAMS.save(deps.storage, stream_id, &stream)?;
+
+    let msg: CosmosMsg = match &stream.asset {
+        VestingAsset::Native { denom } => {
+            VESTING_RESERVED.update(deps.storage, denom, |reserved| -> StdResult<_> {
+                Ok(reserved.unwrap_or_default().checked_sub(amount)?)
+            })?;
+            BankMsg::Send {
+                to_address: stream.recipient.to_string(),
+                amount: coins(amount.u128(), denom),
+            }
+            .into()
+        }
+        VestingAsset::Cw20 { address } => CosmosMsg::Wasm(Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: stream.recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }),
+    };
+
+    Ok(Response::new().add_message(msg).add_event(
+        timelock_event("claim_vesting")
+            .add_attribute("stream_id", stream_id.to_string())
+            .add_attribute("recipient", stream.recipient)
+            .add_attribute("amount", amount)
+            .add_attribute("claimed", stream.claimed),
+    ))
+}
+
+pub fn execute_add_hook(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    addr: String,
+    on_failure: HookFailurePolicy,
+) -> Result<Response, ContractError> {
+    let timelock = CONFIG.load(deps.storage)?;
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
+    }
+
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let hook = deps.api.addr_validate(&addr)?;
+    if HOOKS.has(deps.storage, &hook) {
+        return Err(ContractError::HookAlreadyRegistered { address: addr });
+    }
+    HOOKS.save(deps.storage, &hook, &on_failure)?;
+
+    Ok(Response::new().add_event(
+        timelock_event("add_hook")
+            .add_attribute("sender", info.sender)
+            .add_attribute("hook", hook),
+    ))
+}
+
+pub fn execute_remove_hook(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    let timelock = CONFIG.load(deps.storage)?;
+    if timelock.frozen {
+        return Err(ContractError::TimelockFrozen {});
+    }
+
+    if !ADMINS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let hook = deps.api.addr_validate(&addr)?;
+    if !HOOKS.has(deps.storage, &hook) {
+        return Err(ContractError::NotFound { address: addr });
+    }
+    HOOKS.remove(deps.storage, &hook);
+
+    Ok(Response::new().add_event(
+        timelock_event("remove_hook")
+            .add_attribute("sender", info.sender)
+            .add_attribute("hook", hook),
+    ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        // a hook with HookFailurePolicy::Ignore failed, the operation goes through anyway
+        HOOK_REPLY_ID => Ok(Response::new().add_event(
+            timelock_event("hook_failed")
+                .add_attribute("error", msg.result.into_result().err().unwrap_or_default()),
+        )),
+        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id)).into()),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::ForceCancel { operation_id } => sudo_force_cancel(deps, env, operation_id),
+        SudoMsg::ReplaceRoles { admins, proposers } => {
+            sudo_replace_roles(deps, env, admins, proposers)
+        }
+        SudoMsg::Unfreeze {} => sudo_unfreeze(deps, env),
+        SudoMsg::UpdateMinDelay { new_delay } => sudo_update_min_delay(deps, env, new_delay),
+    }
+}
+
+/// keeps the config as it was before a sudo change in CONFIG_HISTORY
+fn record_config_change(
+    storage: &mut dyn Storage,
+    env: &Env,
+    action: &str,
+    previous: Timelock,
+    previous_admins: Option<Vec<Addr>>,
+    previous_proposers: Option<Vec<Addr>>,
+) -> StdResult<()> {
+    let id = CONFIG_HISTORY_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    CONFIG_HISTORY_SEQ.save(storage, &id)?;
+    CONFIG_HISTORY.save(
+        storage,
+        id,
+        &ConfigChange {
+            action: action.to_string(),
+            height: env.block.height,
+            time: env.block.time,
+            previous,
+            previous_admins,
+            previous_proposers,
+        },
+    )
+}
+
+pub fn sudo_force_cancel(
+    deps: DepsMut,
+    _env: Env,
+    operation_id: Uint64,
+) -> Result<Response, ContractError> {
+    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+
+    if operation.status == OperationStatus::Done {
+        return Err(ContractError::NotDeletable {});
+    }
+
+    // the cancelled version stays visible in the operation history
+    operation.status = OperationStatus::Cancelled;
+    OPERATION_HISTORY.save(
+        deps.storage,
+        (operation_id.u64(), operation.revision),
+        &operation,
+    )?;
+    remove_operation(deps.storage, &operation)?;
+
+    let hooks = hook_messages(
+        deps.storage,
+        OperationHookMsg::Cancelled {
+            operation_id,
+            sender: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(hooks)
+        .add_event(operation_event("sudo_force_cancel", &operation)))
+}
+
+pub fn sudo_replace_roles(
+    deps: DepsMut,
+    env: Env,
+    admins: Option<Vec<String>>,
+    proposers: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let timelock = CONFIG.load(deps.storage)?;
+
+    let mut previous_admins = None;
+    if let Some(admin_list) = admins {
+        let mut admins = vec![];
+        for admin in admin_list {
+            admins.push(deps.api.addr_validate(&admin)?);
+        }
+        if !admins.contains(&env.contract.address) {
+            admins.push(env.contract.address.clone());
+        }
+        previous_admins = Some(replace_members(deps.storage, ADMINS, &admins)?);
+    }
+    let mut previous_proposers = None;
+    if let Some(proposer_list) = proposers {
+        let mut proposers = vec![];
+        for proposer in proposer_list {
+            proposers.push(deps.api.addr_validate(&proposer)?);
+        }
+        previous_proposers = Some(replace_members(deps.storage, PROPOSERS, &proposers)?);
+    }
+
+    record_config_change(
+        deps.storage,
+        &env,
+        "replace_roles",
+        timelock,
+        previous_admins,
+        previous_proposers,
+    )?;
+
+    Ok(Response::new().add_event(timelock_event("sudo_replace_roles")))
+}
+
+/// replaces all members of a role and returns the previous ones
+fn replace_members(
+    storage: &mut dyn Storage,
+    role: Map<&Addr, Empty>,
+    members: &[Addr],
+) -> StdResult<Vec<Addr>> {
+    let previous = role
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for member in &previous {
+        role.remove(storage, member);
+    }
+    for member in members {
+        role.save(storage, member, &Empty {})?;
+    }
+    Ok(previous)
+}
+
+pub fn sudo_unfreeze(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
+    let previous = timelock.clone();
+
+    timelock.frozen = false;
+
+    record_config_change(deps.storage, &env, "unfreeze", previous, None, None)?;
+    CONFIG.save(deps.storage, &timelock)?;
+
+    Ok(Response::new().add_event(timelock_event("sudo_unfreeze")))
+}
+
+pub fn sudo_update_min_delay(
+    deps: DepsMut,
+    env: Env,
+    new_delay: Duration,
+) -> Result<Response, ContractError> {
+    let mut timelock = CONFIG.load(deps.storage)?;
+    let previous = timelock.clone();
+
+    timelock.min_time_delay = new_delay;
+
+    record_config_change(deps.storage, &env, "update_min_delay", previous, None, None)?;
+    CONFIG.save(deps.storage, &timelock)?;
+
+    Ok(Response::new().add_event(duration_attribute(
+        timelock_event("sudo_update_min_delay"),
+        "min_delay",
+        &timelock.min_time_delay,
+    )))
+}
+
+/// Timelock config as stored before the role members moved into their own maps
+#[derive(Serialize, Deserialize)]
+struct LegacyTimelock {
+    #[serde(default)]
+    admins: Vec<Addr>,
+    #[serde(default)]
+    proposers: Vec<Addr>,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    if version.contract != CONTRACT_NAME || version.version != PREVIOUS_VERSION {
+        return Err(ContractError::CannotMigrate {
+            contract: version.contract,
+            version: version.version,
+        });
+    }
+
+    let legacy: LegacyTimelock = Item::new("timelock").load(deps.storage)?;
+    for admin in &legacy.admins {
+        ADMINS.save(deps.storage, admin, &Empty {})?;
+    }
+    for proposer in &legacy.proposers {
+        PROPOSERS.save(deps.storage, proposer, &Empty {})?;
+    }
+    // saving drops the role lists from the stored config
+    let timelock = CONFIG.load(deps.storage)?;
+    CONFIG.save(deps.storage, &timelock)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_event(
+        timelock_event("migrate")
+            .add_attribute("admins", legacy.admins.len().to_string())
+            .add_attribute("proposers", legacy.proposers.len().to_string()),
+    ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    query_custom::<Empty>(deps, env, msg)
+}
+
+/// Library query entry point for chains that schedule their own custom messages,
+/// exported along with `execute_custom` so operations are simulated with the same `C`
+pub fn query_custom<C>(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary>
+where
+    C: CustomMsg + DeserializeOwned,
+{
+    match msg {
+        QueryMsg::GetOperationStatus { operation_id } => {
+            to_binary(&query_get_operation_status(deps, operation_id)?)
+        }
+        QueryMsg::GetExecutionTime { operation_id } => {
+            to_binary(&query_get_execution_time(deps, operation_id)?)
+        }
+        QueryMsg::GetAdmins {} => to_binary(&query_get_admins(deps)?),
+        QueryMsg::GetOperations {
+            start_after,
+            limit,
+            omit_large_fields,
+        } => to_binary(&query_get_operations(
+            deps,
+            start_after,
+            limit,
+            omit_large_fields,
+        )?),
+        QueryMsg::GetOperationsByTag {
+            tag,
+            start_after,
+            limit,
+            omit_large_fields,
+        } => to_binary(&query_get_operations_by_tag(
+            deps,
+            tag,
+            start_after,
+            limit,
+            omit_large_fields,
+        )?),
+        QueryMsg::GetMinDelay {} => to_binary(&query_get_min_delay(deps)?),
+        QueryMsg::GetInputLimits {} => to_binary(&query_get_input_limits(deps)?),
+        QueryMsg::GetProposerLimits {} => to_binary(&query_get_proposer_limits(deps)?),
+        QueryMsg::GetProposerUsage { proposer } => {
+            to_binary(&query_get_proposer_usage(deps, env, proposer)?)
+        }
+        QueryMsg::GetProposers {} => to_binary(&query_get_proposers(deps)?),
+        QueryMsg::GetExecutors { operation_id } => {
+            to_binary(&query_get_executors(deps, operation_id)?)
+        }
+        QueryMsg::GetOperationHistory {
+            operation_id,
+            start_after,
+            limit,
+            omit_large_fields,
+        } => to_binary(&query_get_operation_history(
+            deps,
+            operation_id,
+            start_after,
+            limit,
+            omit_large_fields,
+        )?),
+        QueryMsg::GetCw20Balances { start_after, limit } => {
+            to_binary(&query_get_cw20_balances(deps, start_after, limit)?)
+        }
+        QueryMsg::GetVestingStream { stream_id } => {
+            to_binary(&query_get_vesting_stream(deps, env, stream_id)?)
+        }
+        QueryMsg::GetVestingStreams { start_after, limit } => {
+            to_binary(&query_get_vesting_streams(deps, env, start_after, limit)?)
+        }
+        QueryMsg::GetHooks {} => to_binary(&query_get_hooks(deps)?),
+        QueryMsg::GetConfigHistory { start_after, limit } => {
+            to_binary(&query_get_config_history(deps, start_after, limit)?)
+        }
+        QueryMsg::ListAdmins { start_after, limit } => {
+            to_binary(&query_list_admins(deps, start_after, limit)?)
+        }
+        QueryMsg::ListProposers { start_after, limit } => {
+            to_binary(&query_list_proposers(deps, start_after, limit)?)
+        }
+        QueryMsg::IsAdmin { address } => to_binary(&query_is_admin(deps, address)?),
+        QueryMsg::IsProposer { address } => to_binary(&query_is_proposer(deps, address)?),
+        QueryMsg::ListGuardians { start_after, limit } => {
+            to_binary(&query_list_guardians(deps, start_after, limit)?)
+        }
+        QueryMsg::IsGuardian { address } => to_binary(&query_is_guardian(deps, address)?),
+        QueryMsg::GetRevivalApprovals { operation_id } => {
+            to_binary(&query_get_revival_approvals(deps, operation_id)?)
+        }
+        QueryMsg::GetExpeditePolicy {} => to_binary(&query_get_expedite_policy(deps)?),
+        QueryMsg::GetExpediteApprovals { operation_id } => {
+            to_binary(&query_get_expedite_approvals(deps, operation_id)?)
+        }
+        QueryMsg::SimulateOperation { operation_id } => {
+            to_binary(&query_simulate_operation::<C>(deps, env, operation_id)?)
+        }
+    }
+}
+
+pub fn query_get_operation_status(deps: Deps, operation_id: Uint64) -> StdResult<OperationStatus> {
+    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    Ok(operation.status)
+}
+
+pub fn query_get_execution_time(deps: Deps, operation_id: Uint64) -> StdResult<String> {
+    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    Ok(operation.execution_time.to_string())
+}
+
+pub fn query_get_admins(deps: Deps) -> StdResult<Vec<Addr>> {
+    ADMINS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+pub fn query_get_operations(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    omit_large_fields: Option<bool>,
+) -> StdResult<OperationListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let operations: StdResult<Vec<_>> = OPERATION_LIST
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+
+    Ok(operation_list(
+        operations?.into_iter().map(|l| l.1),
+        omit_large_fields.unwrap_or(false),
+    ))
+}
+
+pub fn query_get_operations_by_tag(
+    deps: Deps,
+    tag: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    omit_large_fields: Option<bool>,
+) -> StdResult<OperationListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let operations: StdResult<Vec<_>> = OPERATION_TAGS
+        .prefix(&tag)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| OPERATION_LIST.load(deps.storage, id?))
+        .collect();
+
+    Ok(operation_list(
+        operations?.into_iter(),
+        omit_large_fields.unwrap_or(false),
+    ))
+}
+
+fn operation_list(
+    operations: impl Iterator<Item = Operation>,
+    omit_large_fields: bool,
+) -> OperationListResponse {
+    OperationListResponse {
+        operationList: operations
+            .map(|operation| {
+                let operation = OperationResponse::from(operation);
+                if omit_large_fields {
+                    operation.without_large_fields()
+                } else {
+                    operation
+                }
+            })
+            .collect(),
+    }
+}
+
+pub fn query_get_min_delay(deps: Deps) -> StdResult<String> {
+    let timelock = CONFIG.load(deps.storage)?;
+    Ok(timelock.min_time_delay.to_string())
+}
+
+pub fn query_get_input_limits(deps: Deps) -> StdResult<InputLimits> {
+    let timelock = CONFIG.load(deps.storage)?;
+    Ok(timelock.limits)
+}
+
+pub fn query_get_proposer_limits(deps: Deps) -> StdResult<ProposerLimits> {
+    let timelock = CONFIG.load(deps.storage)?;
+    Ok(timelock.proposer_limits)
+}
+
+pub fn query_get_proposer_usage(
+    deps: Deps,
+    env: Env,
+    proposer: String,
+) -> StdResult<ProposerUsageResponse> {
+    let proposer = deps.api.addr_validate(&proposer)?;
+    let timelock = CONFIG.load(deps.storage)?;
+    let usage = current_usage(deps.storage, &env, &timelock.proposer_limits, &proposer)?;
+    Ok(ProposerUsageResponse {
+        proposer,
+        pending: usage.pending,
+        scheduled_in_window: usage.scheduled_in_window,
+        window_start: usage.window_start,
+    })
+}
+
+pub fn query_get_proposers(deps: Deps) -> StdResult<Vec<Addr>> {
+    PROPOSERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+fn list_members(
+    deps: Deps,
+    role: Map<&Addr, Empty>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = maybe_addr(deps.api, start_after)?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    role.keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+pub fn query_list_admins(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    list_members(deps, ADMINS, start_after, limit)
+}
+
+pub fn query_list_proposers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    list_members(deps, PROPOSERS, start_after, limit)
+}
+
+pub fn query_is_admin(deps: Deps, address: String) -> StdResult<bool> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(ADMINS.has(deps.storage, &address))
+}
+
+pub fn query_is_proposer(deps: Deps, address: String) -> StdResult<bool> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(PROPOSERS.has(deps.storage, &address))
+}
+
+pub fn query_list_guardians(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    list_members(deps, GUARDIANS, start_after, limit)
+}
+
+pub fn query_is_guardian(deps: Deps, address: String) -> StdResult<bool> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(GUARDIANS.has(deps.storage, &address))
+}
+
+pub fn query_get_revival_approvals(deps: Deps, operation_id: Uint64) -> StdResult<Vec<Addr>> {
+    admin_approvals(deps.storage, REVIVAL_APPROVALS, operation_id.u64())
+}
+
+pub fn query_get_expedite_policy(deps: Deps) -> StdResult<Option<ExpeditePolicy>> {
+    let timelock = CONFIG.load(deps.storage)?;
+    Ok(timelock.expedite_policy)
+}
+
+pub fn query_get_expedite_approvals(deps: Deps, operation_id: Uint64) -> StdResult<Vec<Addr>> {
+    admin_approvals(deps.storage, EXPEDITE_APPROVALS, operation_id.u64())
+}
+
+pub fn query_get_executors(deps: Deps, operation_id: Uint64) -> StdResult<Vec<Addr>> {
+    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    Ok(operation.executors.unwrap_or_default())
+}
+
+pub fn query_get_operation_history(
+    deps: Deps,
+    operation_id: Uint64,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+    omit_large_fields: Option<bool>,
+) -> StdResult<OperationListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let revisions: StdResult<Vec<_>> = OPERATION_HISTORY
+        .prefix(operation_id.u64())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+
+    Ok(operation_list(
+        revisions?.into_iter().map(|l| l.1),
+        omit_large_fields.unwrap_or(false),
+    ))
+}
+
+pub fn query_get_cw20_balances(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Cw20BalancesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = maybe_addr(deps.api, start_after)?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let balances: StdResult<Vec<_>> = CW20_BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(address, amount)| Cw20Coin {
+                address: address.to_string(),
+                amount,
+            })
+        })
+        .collect();
+
+    Ok(Cw20BalancesResponse {
+        balances: balances?,
+    })
+}
+
+pub fn query_get_vesting_stream(
+    deps: Deps,
+    env: Env,
+    stream_id: u64,
+) -> StdResult<VestingStreamResponse> {
+    let stream = VESTING_STREAMS.load(deps.storage, stream_id)?;
+    Ok(VestingStreamResponse::new(stream, env.block.time))
+}
+
+pub fn query_get_vesting_streams(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<VestingStreamListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let streams: StdResult<Vec<_>> = VESTING_STREAMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, stream)| VestingStreamResponse::new(stream, env.block.time)))
+        .collect();
+
+    Ok(VestingStreamListResponse { streams: streams? })
+}
+
+pub fn query_get_hooks(deps: Deps) -> StdResult<HooksResponse> {
+    let hooks: StdResult<Vec<_>> = HOOKS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(addr, on_failure)| HookResponse { addr, on_failure }))
+        .collect();
+
+    Ok(HooksResponse { hooks: hooks? })
+}
+
+pub fn query_get_config_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ConfigHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let changes: StdResult<Vec<_>> = CONFIG_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, change)| change))
+        .collect();
+
+    Ok(ConfigHistoryResponse { changes: changes? })
+}
+
+pub fn query_simulate_operation<C>(
+    deps: Deps,
+    env: Env,
+    operation_id: Uint64,
+) -> StdResult<SimulateOperationResponse>
+where
+    C: CustomMsg + DeserializeOwned,
+{
+    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let mut warnings = vec![];
+
+    match operation.status {
+        OperationStatus::Done => warnings.push("operation is already executed".to_string()),
+        OperationStatus::Cancelled => warnings.push("operation is cancelled".to_string()),
+        OperationStatus::Vetoed => warnings.push("operation is vetoed".to_string()),
+        _ => {}
+    }
+    if let Some(executors) = &operation.executors {
+        if executors.is_empty() {
+            warnings.push("executor list is empty, nobody can execute the operation".to_string());
+        }
+    }
+
+    //contracts the payload calls and the funds it sends out of the timelock
+    let mut contracts = vec![];
+    let mut funds: Vec<Coin> = vec![];
+    match operation.payload_kind {
+        PayloadKind::WasmExecute => contracts.push(operation.target.to_string()),
+        PayloadKind::CosmosMsg => match from_binary::<CosmosMsg<C>>(&operation.data) {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                funds: attached,
+                ..
+            })) => {
+                contracts.push(contract_addr);
+                funds = attached;
+            }
+            Ok(CosmosMsg::Bank(BankMsg::Send { amount, .. })) => funds = amount,
+            Ok(_) => {}
+            Err(_) => warnings.push("payload can not be decoded, it is not simulated".to_string()),
+        },
+    }
+
+    for contract in contracts {
+        let info: StdResult<ContractInfoResponse> = deps.querier.query(
+            &WasmQuery::ContractInfo {
+                contract_addr: contract.clone(),
+            }
+            .into(),
+        );
+        if info.is_err() {
+            warnings.push(format!("target {} is not a contract", contract));
+        }
+    }
+
+    for coin in funds {
+        if available_native_balance(deps, &env, &coin.denom)? < coin.amount {
+            warnings.push(format!(
+                "timelock balance does not cover {}{}",
+                coin.amount, coin.denom
+            ));
+        }
+    }
+
+    if let Some((token, amount)) = tracked_cw20_outflow(
+        deps.storage,
+        &operation.payload_kind,
+        &operation.target,
+        &operation.data,
+    )? {
+        if check_cw20_balance(deps.storage, &token, amount).is_err() {
+            warnings.push(format!(
+                "tracked cw20 balance of {} does not cover {}",
+                token, amount
+            ));
+        }
+    }
+
+    Ok(SimulateOperationResponse {
+        operation_id,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{ContractResult, Empty, SystemError, SystemResult, Timestamp};
+    use cw_utils::Scheduled;
+
+    /// custom message of a chain scheduling its own messages
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum TestCustomMsg {
+        Reset,
+    }
+
+    impl CustomMsg for TestCustomMsg {}
+
+    /// value of an attribute of a timelock event
+    fn attribute<'a>(event: &'a Event, key: &str) -> &'a str {
+        assert_eq!(event.ty, "timelock");
+        &event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .unwrap_or_else(|| panic!("missing attribute {}", key))
+            .value
+    }
+
+    #[test]
+    fn test_no_executers() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::Some(vec!["owner".to_string(), "new_one".to_string()]),
+            proposers: vec!["prop1".to_string(), "prop2".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+        let description = "test desc".to_string();
+        let title = "Title Example ".to_string();
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        println!("{:?}", res);
+
+        let data = to_binary(&"data").unwrap();
+        // try Schedule() with sender "creator"
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //change sender to prop1
+        let info = mock_info("prop1", &[]);
+        //try Schedule() sender "prop1" execution_time < env.block.time
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(1)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::MinDelayNotSatisfied {});
+
+        //Schedule() sender "prop1" execution_time > env.block.time && min_delay_time > execution_time - env.block.time
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        let res = query_get_execution_time(deps.as_ref(), Uint64::new(1));
+        println!("{:?}, {}", res, env.block.time);
+
+        //try Execute() sender "prop1" execution_time > env.block.time
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap_err();
+        assert_eq!(res, ContractError::Unexpired {});
+
+        //time pass
+        env.block.time = Timestamp::from_seconds(120);
+        //try Execute() sender "prop1" execution_time <= env.block.time executors "none"
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap();
+        println!("{:?}", res);
+    }
+
+    #[test]
+    fn test_with_executors() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::Some(vec!["owner".to_string(), "newone".to_string()]),
+            proposers: vec!["prop1".to_string(), "prop2".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+        let title = "Title Example ".to_string();
+
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        println!("{:?}", res);
+
+        let data = to_binary(&"data").unwrap();
+        let description = "test desc".to_string();
+        //change sender to prop1
+        let info = mock_info("prop1", &[]);
+
+        //Schedule() sender "prop1" execution_time > env.block.time && min_delay_time > execution_time - env.block.time
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::Some(vec!["exec1".to_string(), "exec2".to_string()]),
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        let res = query_get_operations(deps.as_ref(), Option::Some(0u64), Option::Some(1u32), None)
+            .unwrap();
+        println!("{:?}", res);
+        //time pass
+        env.block.time = Timestamp::from_seconds(120);
+
+        //try Execute() sender "prop1" execution_time <= env.block.time executors "exec1, exec2"
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        let info = mock_info("exec1", &[]);
+        //Execute() sender "exec1" execution_time <= env.block.time executors "exec1, exec2"
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap();
+        println!("{:?}", res);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::Some(vec!["owner".to_string(), "newone".to_string()]),
+            proposers: vec!["prop1".to_string(), "prop2".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+        let title = "Title Example ".to_string();
+
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        println!("{:?}", res);
+
+        let data = to_binary(&"data").unwrap();
         let description = "test desc".to_string();
+
         //change sender to prop1
         let info = mock_info("prop1", &[]);
 
-        //Schedule() sender "prop1" execution_time > env.block.time && min_delay_time > execution_time - env.block.time
-        let res = execute_schedule(
+        //Schedule() sender "prop1" execution_time > env.block.time && min_delay_time > execution_time - env.block.time
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        //time pass
+        env.block.time = Timestamp::from_seconds(120);
+
+        //Execute() sender "prop1" executors ""
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap();
+        println!("{:?}", res);
+
+        //try Cancel() sender "prop1" operation_id "1" status "OperationStatus::Done"
+        let res =
+            execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap_err();
+        assert_eq!(res, ContractError::NotDeletable {});
+
+        //Schedule() sender "prop1"
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(140)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        //Cancel() sender "prop1" operation_id "2" status "OperationStatus::Pending"
+        let res = execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2)).unwrap();
+        println!("{:?}", res);
+
+        //try Cancel() sender "nobody" operation_id "2" admin "creator" proposers "prop1, prop2"
+        let res =
+            execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2)).unwrap_err();
+        println!("{:?}", res);
+
+        //Schedule() sender "prop1"
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            title.clone(),
+            description.clone(),
+            Scheduled::AtTime(Timestamp::from_seconds(140)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        let info = mock_info("nobody", &[]);
+        //try Cancel() sender "nobody" operation_id "3" admin "creator" proposers "prop1, prop2"
+        let res =
+            execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(3)).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_add_remove_proposer() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec![],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        println!("{:?}", res);
+
+        //try remove_proposer sender "creator" proposer_address "prop1" proposers ""
+        let res = execute_remove_proposer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "prop1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::NotFound {
+                address: "prop1".to_string()
+            }
+        );
+
+        let info = mock_info("no_admin", &[]);
+        //try remove_proposer sender "no_admin" proposer_address "prop1" proposers ""
+        let res = execute_remove_proposer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "prop1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //try add_proposer sender "no_admin" proposer_address "prop1" proposers ""
+        let res = execute_add_proposer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "prop1".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        let info = mock_info("creator", &[]);
+        //add_proposer sender "creator" proposer_address "prop1" proposers ""
+        let res = execute_add_proposer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "prop1".to_string(),
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        //remove_proposer sender "no_admin" proposer_address "prop1" proposers "prop1"
+        let res = execute_remove_proposer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "prop1".to_string(),
+        )
+        .unwrap();
+        println!("{:?}", res);
+    }
+
+    #[test]
+    fn test_update_min_delay() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec![],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        println!("{:?}", res);
+
+        //update_min_delay() sender "creator"
+        let res = execute_update_min_delay(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Duration::Time(100),
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        let info = mock_info("no_admin", &[]);
+        //try update_min_delay() sender "no_admin"
+        let res = execute_update_min_delay(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Duration::Time(100),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_revoke_admin() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec![],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        println!("{:?}", res);
+
+        //try revoke_admin() sender "creator" admin_address "not_in_it" admin "creator"
+        let res = execute_revoke_admin(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "not_in_it".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::NotFound {
+                address: "not_in_it".to_string()
+            }
+        );
+
+        //revoke_admin() sender "creator" admin_address "creator" admin "creator"
+        let res = execute_revoke_admin(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "creator".to_string(),
+        )
+        .unwrap();
+        println!("{:?}", res);
+
+        //try revoke_admin() sender "creator" admin_address "creator" admin ""
+        let res = execute_revoke_admin(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "creator".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_amend() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string(), "prop2".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        let res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        println!("{:?}", res);
+
+        let data = to_binary(&"data").unwrap();
+        let info = mock_info("prop1", &[]);
+        //Schedule() sender "prop1" execution_time 120
+        execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data,
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+
+        //try Amend() sender "prop2" which is neither proposer of the operation nor admin
+        let res = execute_amend::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop2", &[]),
+            Uint64::new(1),
+            Option::None,
+            Option::Some("Title".to_string()),
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //Amend() sender "prop1" title only, execution time is kept
+        env.block.time = Timestamp::from_seconds(115);
+        execute_amend::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Uint64::new(1),
+            Option::None,
+            Option::Some("Title".to_string()),
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.title, "Title".to_string());
+        assert_eq!(operation.revision, 1);
+        assert_eq!(
+            operation.execution_time,
+            Scheduled::AtTime(Timestamp::from_seconds(120))
+        );
+
+        //Amend() sender "creator" (admin) payload change restarts the delay
+        execute_amend::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            Uint64::new(1),
+            Option::Some(to_binary(&"new data").unwrap()),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(
+            operation.execution_time,
+            Scheduled::AtTime(Timestamp::from_seconds(125))
+        );
+
+        //try Amend() execution_time earlier than min delay
+        let res = execute_amend::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Uint64::new(1),
+            Option::None,
+            Option::None,
+            Option::None,
+            Option::Some(Scheduled::AtTime(Timestamp::from_seconds(120))),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::MinDelayNotSatisfied {});
+
+        let res =
+            query_get_operation_history(deps.as_ref(), Uint64::new(1), None, None, None).unwrap();
+        assert_eq!(res.operationList.len(), 2);
+        assert_eq!(res.operationList[0].title, "Title Example ".to_string());
+        assert_eq!(res.operationList[1].data, to_binary(&"data").unwrap());
+
+        //try Amend() on an executed operation
+        env.block.time = Timestamp::from_seconds(125);
+        execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
+        let res = execute_amend::<Empty>(
+            deps.as_mut(),
+            env,
+            info,
+            Uint64::new(1),
+            Option::None,
+            Option::Some("Title".to_string()),
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Executed {});
+    }
+
+    #[test]
+    fn test_amend_payload() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(100, "ujuno"));
+
+        //Receive() 100 tokens from "token"
+        execute_receive(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            Cw20ReceiveMsg {
+                sender: "depositor".to_string(),
+                amount: Uint128::new(100),
+                msg: Binary::default(),
+            },
+        )
+        .unwrap();
+
+        let info = mock_info("prop1", &[]);
+        //ScheduleCw20Transfer() covered by the balance
+        execute_schedule_cw20_transfer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "token".to_string(),
+            "recipient".to_string(),
+            Uint128::new(60),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+        )
+        .unwrap();
+
+        //try Amend() the transfer to more than the tracked balance
+        let res = execute_amend::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Uint64::new(1),
+            Option::Some(
+                to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::new(150),
+                })
+                .unwrap(),
+            ),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientCw20Balance {
+                token: "token".to_string(),
+                balance: Uint128::new(100),
+                amount: Uint128::new(150),
+            }
+        );
+
+        //ScheduleMsg() a bank message
+        execute_schedule_msg(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            CosmosMsg::<Empty>::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(100, "ujuno"),
+            }),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+
+        //try Amend() it to a payload that is not a CosmosMsg
+        let res = execute_amend::<Empty>(
+            deps.as_mut(),
+            env,
+            info,
+            Uint64::new(2),
+            Option::Some(to_binary(&"data").unwrap()),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert!(matches!(res, ContractError::Std(StdError::ParseErr { .. })));
+        let operation = OPERATION_LIST.load(&deps.storage, 2u64).unwrap();
+        assert_eq!(operation.revision, 0);
+    }
+
+    #[test]
+    fn test_recurring() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let data = to_binary(&"data").unwrap();
+        let info = mock_info("prop1", &[]);
+
+        //try Schedule() with an interval of a different kind than execution_time
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data.clone(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+            Option::Some(Recurrence {
+                interval: Duration::Height(10),
+                end: None,
+                max_executions: None,
+            }),
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidRecurrence {});
+
+        //Schedule() every 50 seconds, at most 3 times
+        execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "target".to_string(),
+            data,
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+            Option::Some(Recurrence {
+                interval: Duration::Time(50),
+                end: None,
+                max_executions: Some(3),
+            }),
+            Option::None,
+        )
+        .unwrap();
+
+        //first execution reschedules the operation
+        env.block.time = Timestamp::from_seconds(120);
+        execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.status, OperationStatus::Pending);
+        assert_eq!(operation.execution_count, 1);
+        assert_eq!(
+            operation.execution_time,
+            Scheduled::AtTime(Timestamp::from_seconds(170))
+        );
+
+        //try Execute() before the next trigger time
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap_err();
+        assert_eq!(res, ContractError::Unexpired {});
+
+        //a late execution skips the missed periods at 220 and 270
+        env.block.time = Timestamp::from_seconds(290);
+        execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.status, OperationStatus::Pending);
+        assert_eq!(operation.execution_count, 2);
+        assert_eq!(
+            operation.execution_time,
+            Scheduled::AtTime(Timestamp::from_seconds(320))
+        );
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap_err();
+        assert_eq!(res, ContractError::Unexpired {});
+
+        //third execution ends the recurrence
+        env.block.time = Timestamp::from_seconds(320);
+        execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.status, OperationStatus::Done);
+        assert_eq!(operation.execution_count, 3);
+
+        env.block.time = Timestamp::from_seconds(370);
+        let res = execute_execute::<Empty>(deps.as_mut(), env, info, Uint64::new(1)).unwrap_err();
+        assert_eq!(res, ContractError::Executed {});
+    }
+
+    #[test]
+    fn test_cw20_treasury() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        //Receive() 100 tokens from "token"
+        execute_receive(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            Cw20ReceiveMsg {
+                sender: "depositor".to_string(),
+                amount: Uint128::new(100),
+                msg: Binary::default(),
+            },
+        )
+        .unwrap();
+        let res = query_get_cw20_balances(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            res.balances,
+            vec![Cw20Coin {
+                address: "token".to_string(),
+                amount: Uint128::new(100)
+            }]
+        );
+
+        let info = mock_info("prop1", &[]);
+        //try ScheduleCw20Transfer() more than the tracked balance
+        let res = execute_schedule_cw20_transfer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "token".to_string(),
+            "recipient".to_string(),
+            Uint128::new(101),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientCw20Balance {
+                token: "token".to_string(),
+                balance: Uint128::new(100),
+                amount: Uint128::new(101),
+            }
+        );
+
+        //ScheduleCw20Transfer() and ScheduleCw20Send() both covered by the balance
+        execute_schedule_cw20_transfer(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "token".to_string(),
+            "recipient".to_string(),
+            Uint128::new(60),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+        )
+        .unwrap();
+        execute_schedule_cw20_send(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "token".to_string(),
+            "contract".to_string(),
+            Uint128::new(60),
+            Binary::default(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(120)),
+            Option::None,
+        )
+        .unwrap();
+
+        env.block.time = Timestamp::from_seconds(120);
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::new(60),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        //try Execute() the send which the remaining balance no longer covers
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2))
+                .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientCw20Balance {
+                token: "token".to_string(),
+                balance: Uint128::new(40),
+                amount: Uint128::new(60),
+            }
+        );
+
+        //ScheduleMsg() transfers of the token are checked and tracked as well
+        let transfer = |amount: u128| -> CosmosMsg {
+            CosmosMsg::Wasm(Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        let res = execute_schedule_msg(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            transfer(50),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(130)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientCw20Balance {
+                token: "token".to_string(),
+                balance: Uint128::new(40),
+                amount: Uint128::new(50),
+            }
+        );
+        execute_schedule_msg(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            transfer(30),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(Timestamp::from_seconds(130)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        env.block.time = Timestamp::from_seconds(130);
+        execute_execute::<Empty>(deps.as_mut(), env, info, Uint64::new(3)).unwrap();
+        let res = query_get_cw20_balances(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.balances[0].amount, Uint128::new(10));
+    }
+
+    #[test]
+    fn test_vesting_stream() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(1000, "ujuno"));
+
+        //try CreateVestingStream() sender "creator", not the timelock itself
+        let res = execute_create_vesting_stream(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            "recipient".to_string(),
+            VestingAssetMsg::Native {
+                denom: "ujuno".to_string(),
+            },
+            Uint128::new(1000),
+            Timestamp::from_seconds(100),
+            Timestamp::from_seconds(150),
+            Timestamp::from_seconds(200),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //CreateVestingStream() from an executed operation
+        let timelock = mock_info(env.contract.address.as_str(), &[]);
+        execute_create_vesting_stream(
+            deps.as_mut(),
+            env.clone(),
+            timelock.clone(),
+            "recipient".to_string(),
+            VestingAssetMsg::Native {
+                denom: "ujuno".to_string(),
+            },
+            Uint128::new(1000),
+            Timestamp::from_seconds(100),
+            Timestamp::from_seconds(150),
+            Timestamp::from_seconds(200),
+        )
+        .unwrap();
+
+        //try CreateVestingStream() for funds already reserved by the first stream
+        let res = execute_create_vesting_stream(
+            deps.as_mut(),
+            env.clone(),
+            timelock,
+            "recipient".to_string(),
+            VestingAssetMsg::Native {
+                denom: "ujuno".to_string(),
+            },
+            Uint128::new(1),
+            Timestamp::from_seconds(100),
+            Timestamp::from_seconds(150),
+            Timestamp::from_seconds(200),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientBalance {
+                denom: "ujuno".to_string()
+            }
+        );
+
+        //try ClaimVesting() before the cliff
+        let info = mock_info("recipient", &[]);
+        env.block.time = Timestamp::from_seconds(140);
+        let res = execute_claim_vesting(deps.as_mut(), env.clone(), info.clone(), 1).unwrap_err();
+        assert_eq!(res, ContractError::NothingToClaim {});
+
+        //ClaimVesting() after the cliff pays the linearly vested part
+        env.block.time = Timestamp::from_seconds(160);
+        let res = execute_claim_vesting(deps.as_mut(), env.clone(), info.clone(), 1).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(600, "ujuno"),
+            })
+        );
+
+        env.block.time = Timestamp::from_seconds(180);
+        let res = query_get_vesting_stream(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.vested, Uint128::new(800));
+        assert_eq!(res.claimed, Uint128::new(600));
+        assert_eq!(res.remaining, Uint128::new(400));
+
+        //try ClaimVesting() sender "prop1"
+        let res =
+            execute_claim_vesting(deps.as_mut(), env, mock_info("prop1", &[]), 1).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_hooks() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        //try AddHook() sender "prop1"
+        let res = execute_add_hook(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop1", &[]),
+            "indexer".to_string(),
+            HookFailurePolicy::Ignore,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //AddHook() sender "creator"
+        execute_add_hook(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "indexer".to_string(),
+            HookFailurePolicy::Ignore,
+        )
+        .unwrap();
+        execute_add_hook(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "relay".to_string(),
+            HookFailurePolicy::Revert,
+        )
+        .unwrap();
+        let res = execute_add_hook(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "relay".to_string(),
+            HookFailurePolicy::Ignore,
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::HookAlreadyRegistered {
+                address: "relay".to_string()
+            }
+        );
+
+        //Schedule() notifies both subscribers
+        let res = execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop1", &[]),
+            "target".to_string(),
+            to_binary(&"data").unwrap(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        let hook_msg = OperationHookMsg::Scheduled {
+            operation_id: Uint64::new(1),
+            proposer: Addr::unchecked("prop1"),
+            target: Addr::unchecked("target"),
+            execution_time: Scheduled::AtTime(env.block.time.plus_seconds(10)),
+        };
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::reply_on_error(
+                    hook_msg.clone().into_cosmos_msg("indexer").unwrap(),
+                    HOOK_REPLY_ID
+                ),
+                SubMsg::new(hook_msg.into_cosmos_msg("relay").unwrap()),
+            ]
+        );
+
+        //RemoveHook() sender "creator", Cancel() only notifies the remaining subscriber
+        execute_remove_hook(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "indexer".to_string(),
+        )
+        .unwrap();
+        let res = execute_cancel(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop1", &[]),
+            Uint64::new(1),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            query_get_hooks(deps.as_ref()).unwrap().hooks,
+            vec![HookResponse {
+                addr: Addr::unchecked("relay"),
+                on_failure: HookFailurePolicy::Revert,
+            }]
+        );
+
+        //try AddHook() and RemoveHook() on a frozen Timelock
+        execute_freeze(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        let res = execute_add_hook(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "indexer".to_string(),
+            HookFailurePolicy::Ignore,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::TimelockFrozen {});
+        let res = execute_remove_hook(deps.as_mut(), env, info, "relay".to_string()).unwrap_err();
+        assert_eq!(res, ContractError::TimelockFrozen {});
+    }
+
+    #[test]
+    fn test_sudo() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        execute_freeze(deps.as_mut(), env.clone(), info).unwrap();
+
+        execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop1", &[]),
+            "target".to_string(),
+            to_binary(&"data").unwrap(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+
+        //ForceCancel() keeps the cancelled operation in the history
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::ForceCancel {
+                operation_id: Uint64::new(1),
+            },
+        )
+        .unwrap();
+        assert!(!OPERATION_LIST.has(&deps.storage, 1u64));
+        let res =
+            query_get_operation_history(deps.as_ref(), Uint64::new(1), None, None, None).unwrap();
+        assert_eq!(res.operationList[0].status, OperationStatus::Cancelled);
+
+        //ReplaceRoles() and Unfreeze() on a frozen timelock
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::ReplaceRoles {
+                admins: Some(vec!["council".to_string()]),
+                proposers: Some(vec!["prop2".to_string()]),
+            },
+        )
+        .unwrap();
+        sudo(deps.as_mut(), env.clone(), SudoMsg::Unfreeze {}).unwrap();
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::UpdateMinDelay {
+                new_delay: Duration::Time(100),
+            },
+        )
+        .unwrap();
+
+        let timelock = CONFIG.load(&deps.storage).unwrap();
+        assert!(!timelock.frozen);
+        assert_eq!(timelock.min_time_delay, Duration::Time(100));
+        assert_eq!(
+            query_get_admins(deps.as_ref()).unwrap(),
+            vec![env.contract.address, Addr::unchecked("council")]
+        );
+        assert_eq!(
+            query_get_proposers(deps.as_ref()).unwrap(),
+            vec![Addr::unchecked("prop2")]
+        );
+
+        let res = query_get_config_history(deps.as_ref(), None, None).unwrap();
+        let actions: Vec<_> = res.changes.iter().map(|c| c.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            vec!["replace_roles", "unfreeze", "update_min_delay"]
+        );
+        assert!(res.changes[1].previous.frozen);
+        assert_eq!(
+            res.changes[0].previous_proposers,
+            Some(vec![Addr::unchecked("prop1")])
+        );
+    }
+
+    #[test]
+    fn test_schedule_msg() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(100, "ujuno"));
+
+        //ScheduleMsg() sender "prop1" with a bank message
+        let bank_msg: CosmosMsg = BankMsg::Send {
+            to_address: "recipient".to_string(),
+            amount: coins(100, "ujuno"),
+        }
+        .into();
+        let info = mock_info("prop1", &[]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ScheduleMsg {
+                msg: bank_msg.clone(),
+                title: "Title Example ".to_string(),
+                description: "test desc".to_string(),
+                execution_time: Scheduled::AtTime(Timestamp::from_seconds(120)),
+                executors: None,
+                recurrence: None,
+                metadata: None,
+            },
+        )
+        .unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.payload_kind, PayloadKind::CosmosMsg);
+        assert_eq!(operation.target, env.contract.address);
+
+        //Execute() dispatches the scheduled message itself
+        env.block.time = Timestamp::from_seconds(120);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::Execute {
+                operation_id: Uint64::new(1),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(bank_msg)]);
+    }
+
+    #[test]
+    fn test_role_queries() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::Some(vec!["owner".to_string(), "new_one".to_string()]),
+            proposers: vec![
+                "prop1".to_string(),
+                "prop2".to_string(),
+                "prop3".to_string(),
+            ],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = query_list_proposers(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(
+            res,
+            vec![Addr::unchecked("prop1"), Addr::unchecked("prop2")]
+        );
+        let res = query_list_proposers(deps.as_ref(), Some("prop2".to_string()), None).unwrap();
+        assert_eq!(res, vec![Addr::unchecked("prop3")]);
+
+        let res = query_list_admins(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            res,
+            vec![
+                env.contract.address,
+                Addr::unchecked("new_one"),
+                Addr::unchecked("owner")
+            ]
+        );
+
+        assert!(query_is_proposer(deps.as_ref(), "prop1".to_string()).unwrap());
+        assert!(!query_is_proposer(deps.as_ref(), "owner".to_string()).unwrap());
+        assert!(query_is_admin(deps.as_ref(), "owner".to_string()).unwrap());
+        assert!(!query_is_admin(deps.as_ref(), "creator".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_roles() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        // only the previous version of the contract is migrated
+        set_contract_version(&mut deps.storage, "crates.io:other", PREVIOUS_VERSION).unwrap();
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::CannotMigrate {
+                contract: "crates.io:other".to_string(),
+                version: PREVIOUS_VERSION.to_string(),
+            }
+        );
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, PREVIOUS_VERSION).unwrap();
+
+        // config and operation as stored before the role members had their own maps
+        deps.storage.set(
+            b"timelock",
+            br#"{"admins":["owner"],"proposers":["prop1"],"min_time_delay":{"time":10},"frozen":false}"#,
+        );
+        let legacy_operation = format!(
+            r#"{{"id":"1","status":"Pending","proposer":"prop1","executors":null,"execution_time":{{"at_time":"{}"}},"target":"target","data":"{}","title":"Title","description":"desc"}}"#,
+            env.block.time.plus_seconds(10).nanos(),
+            to_binary(&"data").unwrap()
+        );
+        OPERATION_SEQ
+            .save(&mut deps.storage, &Uint64::new(1))
+            .unwrap();
+        deps.storage
+            .set(&OPERATION_LIST.key(1u64), legacy_operation.as_bytes());
+
+        migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::CannotMigrate {
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        // the operation scheduled before the migration loads and executes
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.payload_kind, PayloadKind::WasmExecute);
+        assert_eq!(operation.revision, 0);
+        assert_eq!(operation.execution_count, 0);
+        env.block.time = env.block.time.plus_seconds(10);
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env, mock_info("prop1", &[]), Uint64::new(1))
+                .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(Execute {
+                contract_addr: "target".to_string(),
+                msg: to_binary(&"data").unwrap(),
+                funds: vec![],
+            })
+        );
+
+        assert!(query_is_admin(deps.as_ref(), "owner".to_string()).unwrap());
+        assert!(query_is_proposer(deps.as_ref(), "prop1".to_string()).unwrap());
+        // the role lists are dropped and the new settings get their defaults
+        let stored = String::from_utf8(deps.storage.get(b"timelock").unwrap()).unwrap();
+        assert!(!stored.contains("admins") && !stored.contains("proposers\""));
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap(),
+            Timelock {
+                min_time_delay: Duration::Time(10),
+                frozen: false,
+                limits: InputLimits::default(),
+                proposer_limits: ProposerLimits::default(),
+                expedite_policy: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_simulate_operation() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == "target" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&ContractInfoResponse::new(1, "creator")).unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::NoSuchContract {
+                addr: "unknown".to_string(),
+            }),
+        });
+
+        let info = mock_info("prop1", &[]);
+        //Schedule() an execute message on a contract
+        execute_schedule(
             deps.as_mut(),
             env.clone(),
             info.clone(),
             "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(120)),
-            Option::Some(vec!["exec1".to_string(), "exec2".to_string()]),
+            to_binary(&"data").unwrap(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
         )
         .unwrap();
-        println!("{:?}", res);
+        let res =
+            query_simulate_operation::<Empty>(deps.as_ref(), env.clone(), Uint64::new(1)).unwrap();
+        assert!(res.warnings.is_empty());
 
+        //Schedule() an execute message on an address that is not a contract
+        execute_schedule(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            "wallet".to_string(),
+            to_binary(&"data").unwrap(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
         let res =
-            query_get_operations(deps.as_ref(), Option::Some(0u64), Option::Some(1u32)).unwrap();
-        println!("{:?}", res);
-        //time pass
-        env.block.time = Timestamp::from_seconds(120);
+            query_simulate_operation::<Empty>(deps.as_ref(), env.clone(), Uint64::new(2)).unwrap();
+        assert_eq!(
+            res.warnings,
+            vec!["target wallet is not a contract".to_string()]
+        );
+
+        //ScheduleMsg() a bank message with no possible executor, the timelock is drained after
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(100, "ujuno"));
+        execute_schedule_msg(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            CosmosMsg::<Empty>::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(100, "ujuno"),
+            }),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::Some(vec![]),
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+        deps.querier
+            .update_balance(env.contract.address.clone(), vec![]);
+        let res =
+            query_simulate_operation::<Empty>(deps.as_ref(), env.clone(), Uint64::new(3)).unwrap();
+        assert_eq!(
+            res.warnings,
+            vec![
+                "executor list is empty, nobody can execute the operation".to_string(),
+                "timelock balance does not cover 100ujuno".to_string(),
+            ]
+        );
+
+        //funding the timelock clears the balance warning
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(100, "ujuno"));
+        let res =
+            query_simulate_operation::<Empty>(deps.as_ref(), env.clone(), Uint64::new(3)).unwrap();
+        assert_eq!(
+            res.warnings,
+            vec!["executor list is empty, nobody can execute the operation".to_string()]
+        );
+
+        //custom payloads are decoded with the custom message type of the chain
+        execute_custom::<TestCustomMsg>(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop1", &[]),
+            ExecuteMsg::ScheduleMsg {
+                msg: CosmosMsg::Custom(TestCustomMsg::Reset),
+                title: "Title Example ".to_string(),
+                description: "test desc".to_string(),
+                execution_time: Scheduled::AtTime(env.block.time.plus_seconds(10)),
+                executors: None,
+                recurrence: None,
+                metadata: None,
+            },
+        )
+        .unwrap();
+        let res =
+            query_simulate_operation::<TestCustomMsg>(deps.as_ref(), env, Uint64::new(4)).unwrap();
+        assert!(res.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_native_outflow_reserved() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(1000, "ujuno"));
+
+        //reserve 600ujuno for a vesting stream
+        let timelock = mock_info(env.contract.address.as_str(), &[]);
+        execute_create_vesting_stream(
+            deps.as_mut(),
+            env.clone(),
+            timelock.clone(),
+            "recipient".to_string(),
+            VestingAssetMsg::Native {
+                denom: "ujuno".to_string(),
+            },
+            Uint128::new(600),
+            Timestamp::from_seconds(100),
+            Timestamp::from_seconds(150),
+            Timestamp::from_seconds(200),
+        )
+        .unwrap();
+
+        //try ScheduleMsg() a bank message spending reserved funds
+        let info = mock_info("prop1", &[]);
+        let res = execute_schedule_msg(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            CosmosMsg::<Empty>::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(500, "ujuno"),
+            }),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientNativeBalance {
+                denom: "ujuno".to_string(),
+                available: Uint128::new(400),
+                amount: Uint128::new(500),
+            }
+        );
+
+        //ScheduleMsg() a wasm message attaching the unreserved funds
+        execute_schedule_msg(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            CosmosMsg::<Empty>::Wasm(WasmMsg::Execute {
+                contract_addr: "target".to_string(),
+                msg: to_binary(&"data").unwrap(),
+                funds: coins(400, "ujuno"),
+            }),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
+            Option::None,
+        )
+        .unwrap();
+
+        //try Execute() after another stream reserved part of those funds
+        execute_create_vesting_stream(
+            deps.as_mut(),
+            env.clone(),
+            timelock,
+            "recipient".to_string(),
+            VestingAssetMsg::Native {
+                denom: "ujuno".to_string(),
+            },
+            Uint128::new(100),
+            Timestamp::from_seconds(100),
+            Timestamp::from_seconds(150),
+            Timestamp::from_seconds(200),
+        )
+        .unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+        let res = execute_execute::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("executor", &[]),
+            Uint64::new(1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InsufficientNativeBalance {
+                denom: "ujuno".to_string(),
+                available: Uint128::new(300),
+                amount: Uint128::new(400),
+            }
+        );
+
+        //Execute() once the timelock is funded again
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(1100, "ujuno"));
+        execute_execute::<Empty>(
+            deps.as_mut(),
+            env,
+            mock_info("executor", &[]),
+            Uint64::new(1),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_input_limits() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::Some(InputLimits {
+                max_title_length: 5,
+                max_description_length: 10,
+                max_data_length: 20,
+                max_metadata_entry_length: 256,
+            }),
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("prop1", &[]);
+        let schedule = |deps: DepsMut, title: &str, description: &str, data: Binary| {
+            execute_schedule(
+                deps,
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                data,
+                title.to_string(),
+                description.to_string(),
+                Scheduled::AtTime(env.block.time.plus_seconds(10)),
+                Option::None,
+                Option::None,
+                Option::None,
+            )
+        };
+
+        let res = schedule(deps.as_mut(), "Title!", "desc", to_binary(&"data").unwrap());
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::TitleTooLong { length: 6, max: 5 }
+        );
+        let res = schedule(
+            deps.as_mut(),
+            "Title",
+            "description",
+            to_binary(&"data").unwrap(),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::DescriptionTooLong {
+                length: 11,
+                max: 10
+            }
+        );
+        let res = schedule(deps.as_mut(), "Title", "desc", Binary::from(vec![0u8; 21]));
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::PayloadTooLarge {
+                length: 21,
+                max: 20
+            }
+        );
+        schedule(deps.as_mut(), "Title", "desc", to_binary(&"data").unwrap()).unwrap();
+
+        //Amend() is held to the same limits
+        let res = execute_amend::<Empty>(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Uint64::new(1),
+            Option::None,
+            Option::None,
+            Option::Some("description".to_string()),
+            Option::None,
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::DescriptionTooLong {
+                length: 11,
+                max: 10
+            }
+        );
+
+        //only admins update the limits
+        let limits = InputLimits {
+            max_title_length: 5,
+            max_description_length: 20,
+            max_data_length: 20,
+            max_metadata_entry_length: 256,
+        };
+        let res =
+            execute_update_input_limits(deps.as_mut(), env.clone(), info.clone(), limits.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute_update_input_limits(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            limits.clone(),
+        )
+        .unwrap();
+        assert_eq!(query_get_input_limits(deps.as_ref()).unwrap(), limits);
+        schedule(
+            deps.as_mut(),
+            "Title",
+            "description",
+            to_binary(&"data").unwrap(),
+        )
+        .unwrap();
+
+        //large fields can be left out of the pages
+        let res = query_get_operations(deps.as_ref(), None, None, Some(true)).unwrap();
+        assert_eq!(res.operationList.len(), 2);
+        assert_eq!(res.operationList[1].title, "Title");
+        assert_eq!(res.operationList[1].description, "");
+        assert_eq!(res.operationList[1].data, Binary::default());
+        let res = query_get_operations(deps.as_ref(), None, None, None).unwrap();
+        assert_eq!(res.operationList[1].description, "description");
+        assert_eq!(res.operationList[1].data, to_binary(&"data").unwrap());
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("prop1", &[]);
+        let schedule = |deps: DepsMut, metadata: Option<OperationMetadata>| {
+            execute_schedule(
+                deps,
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                to_binary(&"data").unwrap(),
+                "Title Example ".to_string(),
+                "test desc".to_string(),
+                Scheduled::AtTime(env.block.time.plus_seconds(10)),
+                Option::None,
+                Option::None,
+                metadata,
+            )
+        };
+
+        let metadata = OperationMetadata {
+            urls: vec!["https://forum.junonetwork.io/t/1".to_string()],
+            content_hash: Some(
+                "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string(),
+            ),
+            tags: vec!["treasury".to_string(), "upgrade".to_string()],
+        };
+
+        let res = schedule(
+            deps.as_mut(),
+            Some(OperationMetadata {
+                content_hash: Some("not a hash".to_string()),
+                ..metadata.clone()
+            }),
+        );
+        assert_eq!(res.unwrap_err(), ContractError::InvalidContentHash {});
+        let res = schedule(
+            deps.as_mut(),
+            Some(OperationMetadata {
+                tags: vec!["treasury".to_string(), "treasury".to_string()],
+                ..metadata.clone()
+            }),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::InvalidMetadata {
+                max: MAX_METADATA_ENTRIES
+            }
+        );
+        let res = schedule(
+            deps.as_mut(),
+            Some(OperationMetadata {
+                tags: vec!["t".repeat(257)],
+                ..metadata.clone()
+            }),
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::MetadataEntryTooLong {
+                length: 257,
+                max: 256
+            }
+        );
+
+        schedule(deps.as_mut(), Some(metadata.clone())).unwrap();
+        schedule(deps.as_mut(), None).unwrap();
+        schedule(
+            deps.as_mut(),
+            Some(OperationMetadata {
+                tags: vec!["treasury".to_string()],
+                ..OperationMetadata::default()
+            }),
+        )
+        .unwrap();
+
+        let res =
+            query_get_operations_by_tag(deps.as_ref(), "treasury".to_string(), None, None, None)
+                .unwrap();
+        let ids: Vec<Uint64> = res.operationList.iter().map(|o| o.id).collect();
+        assert_eq!(ids, vec![Uint64::new(1), Uint64::new(3)]);
+        assert_eq!(res.operationList[0].metadata, Some(metadata));
+
+        //metadata is left out of the pages without large fields
+        let res = query_get_operations(deps.as_ref(), None, None, Some(true)).unwrap();
+        assert_eq!(res.operationList[0].metadata, None);
+
+        let res =
+            query_get_operations_by_tag(deps.as_ref(), "treasury".to_string(), Some(1), None, None)
+                .unwrap();
+        assert_eq!(res.operationList.len(), 1);
+        assert_eq!(res.operationList[0].id, Uint64::new(3));
+
+        let res =
+            query_get_operations_by_tag(deps.as_ref(), "upgrade".to_string(), None, None, None)
+                .unwrap();
+        assert_eq!(res.operationList.len(), 1);
+
+        //cancelled operations leave the index
+        execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
+        let res =
+            query_get_operations_by_tag(deps.as_ref(), "treasury".to_string(), None, None, None)
+                .unwrap();
+        assert_eq!(res.operationList.len(), 1);
+        assert_eq!(res.operationList[0].id, Uint64::new(3));
+    }
+
+    #[test]
+    fn test_proposer_limits() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let msg = InstantiateMsg {
+            admins: Option::None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+            limits: Option::None,
+        };
+        let info = mock_info("creator", &[]);
+
+        // instantiate
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let limits = ProposerLimits {
+            max_pending: Some(2),
+            max_per_window: Some(3),
+            window_seconds: 100,
+        };
+        let res = execute_update_proposer_limits(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("prop1", &[]),
+            limits.clone(),
+        );
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res = execute_update_proposer_limits(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ProposerLimits {
+                window_seconds: 0,
+                ..limits.clone()
+            },
+        );
+        assert_eq!(res.unwrap_err(), ContractError::InvalidProposerLimits {});
+        execute_update_proposer_limits(deps.as_mut(), env.clone(), info, limits.clone()).unwrap();
+        assert_eq!(query_get_proposer_limits(deps.as_ref()).unwrap(), limits);
+
+        let info = mock_info("prop1", &[]);
+        let schedule = |deps: DepsMut, env: &Env| {
+            execute_schedule(
+                deps,
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                to_binary(&"data").unwrap(),
+                "Title Example ".to_string(),
+                "test desc".to_string(),
+                Scheduled::AtTime(env.block.time.plus_seconds(10)),
+                Option::None,
+                Option::None,
+                Option::None,
+            )
+        };
+
+        schedule(deps.as_mut(), &env).unwrap();
+        schedule(deps.as_mut(), &env).unwrap();
+        let res = schedule(deps.as_mut(), &env);
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::TooManyPendingOperations { max: 2 }
+        );
 
-        //try Execute() sender "prop1" execution_time <= env.block.time executors "exec1, exec2"
-        let res =
-            execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+        //cancelling frees a pending slot but not the window
+        execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
+        schedule(deps.as_mut(), &env).unwrap();
+        execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(3)).unwrap();
+        let res = schedule(deps.as_mut(), &env);
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::ScheduleRateLimited {
+                max: 3,
+                window_seconds: 100
+            }
+        );
+        let usage =
+            query_get_proposer_usage(deps.as_ref(), env.clone(), "prop1".to_string()).unwrap();
+        assert_eq!(
+            usage,
+            ProposerUsageResponse {
+                proposer: Addr::unchecked("prop1"),
+                pending: 1,
+                scheduled_in_window: 3,
+                window_start: env.block.time,
+            }
+        );
 
-        let info = mock_info("exec1", &[]);
-        //Execute() sender "exec1" execution_time <= env.block.time executors "exec1, exec2"
-        let res =
-            execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
-        println!("{:?}", res);
+        //executing frees a pending slot, a new window allows scheduling again
+        env.block.time = env.block.time.plus_seconds(100);
+        execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2)).unwrap();
+        let usage =
+            query_get_proposer_usage(deps.as_ref(), env.clone(), "prop1".to_string()).unwrap();
+        assert_eq!(usage.pending, 0);
+        assert_eq!(usage.scheduled_in_window, 0);
+        schedule(deps.as_mut(), &env).unwrap();
+        let usage =
+            query_get_proposer_usage(deps.as_ref(), env.clone(), "prop1".to_string()).unwrap();
+        assert_eq!(usage.pending, 1);
+        assert_eq!(usage.scheduled_in_window, 1);
+        assert_eq!(usage.window_start, env.block.time);
     }
 
     #[test]
-    fn test_cancel() {
+    fn test_guardian_veto() {
         let mut deps = mock_dependencies();
         let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
         let msg = InstantiateMsg {
-            admins: Option::Some(vec!["owner".to_string(), "newone".to_string()]),
-            proposers: vec!["prop1".to_string(), "prop2".to_string()],
+            admins: Option::Some(vec![
+                "admin1".to_string(),
+                "admin2".to_string(),
+                "admin3".to_string(),
+            ]),
+            proposers: vec!["prop1".to_string()],
             min_delay: Duration::Time(10),
+            limits: Option::None,
         };
         let info = mock_info("creator", &[]);
-        let title = "Title Example ".to_string();
 
         // instantiate
-        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        println!("{:?}", res);
-
-        let data = to_binary(&"data").unwrap();
-        let description = "test desc".to_string();
-
-        //change sender to prop1
-        let info = mock_info("prop1", &[]);
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
-        //Schedule() sender "prop1" execution_time > env.block.time && min_delay_time > execution_time - env.block.time
-        let res = execute_schedule(
+        let res = execute_add_guardian(deps.as_mut(), env.clone(), info, "guardian".to_string());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let admin1 = mock_info("admin1", &[]);
+        execute_add_guardian(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(120)),
-            Option::None,
+            admin1.clone(),
+            "guardian".to_string(),
         )
         .unwrap();
-        println!("{:?}", res);
-
-        //time pass
-        env.block.time = Timestamp::from_seconds(120);
-
-        //Execute() sender "prop1" executors ""
-        let res =
-            execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
-        println!("{:?}", res);
-
-        //try Cancel() sender "prop1" operation_id "1" status "OperationStatus::Done"
-        let res =
-            execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap_err();
-        assert_eq!(res, ContractError::NotDeletable {});
+        assert!(query_is_guardian(deps.as_ref(), "guardian".to_string()).unwrap());
 
-        //Schedule() sender "prop1"
-        let res = execute_schedule(
+        let info = mock_info("prop1", &[]);
+        execute_schedule(
             deps.as_mut(),
             env.clone(),
             info.clone(),
             "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(140)),
+            to_binary(&"data").unwrap(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(10)),
+            Option::None,
+            Option::None,
             Option::None,
         )
         .unwrap();
-        println!("{:?}", res);
 
-        //Cancel() sender "prop1" operation_id "2" status "OperationStatus::Pending"
-        let res = execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2)).unwrap();
-        println!("{:?}", res);
+        //only guardians veto
+        let res = execute_veto(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let guardian = mock_info("guardian", &[]);
+        execute_veto(deps.as_mut(), env.clone(), guardian.clone(), Uint64::new(1)).unwrap();
+        assert_eq!(
+            query_get_operation_status(deps.as_ref(), Uint64::new(1)).unwrap(),
+            OperationStatus::Vetoed
+        );
 
-        //try Cancel() sender "nobody" operation_id "2" admin "creator" proposers "prop1, prop2"
+        env.block.time = env.block.time.plus_seconds(20);
         let res =
-            execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2)).unwrap_err();
-        println!("{:?}", res);
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::Vetoed {});
 
-        //Schedule() sender "prop1"
-        let res = execute_schedule(
+        //two of the three admins revive the operation
+        let res = execute_approve_revival(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        execute_approve_revival(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1))
+            .unwrap();
+        let res =
+            execute_approve_revival(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::AlreadyApproved {});
+        assert_eq!(
+            query_get_revival_approvals(deps.as_ref(), Uint64::new(1)).unwrap(),
+            vec![Addr::unchecked("admin1")]
+        );
+        execute_approve_revival(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            "target".to_string(),
-            data.clone(),
-            title.clone(),
-            description.clone(),
-            Scheduled::AtTime(Timestamp::from_seconds(140)),
-            Option::None,
+            mock_info("admin2", &[]),
+            Uint64::new(1),
         )
         .unwrap();
-        println!("{:?}", res);
+        assert!(query_get_revival_approvals(deps.as_ref(), Uint64::new(1))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            query_get_operation_status(deps.as_ref(), Uint64::new(1)).unwrap(),
+            OperationStatus::Pending
+        );
+        let res = execute_approve_revival(deps.as_mut(), env.clone(), admin1, Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::NotVetoed {});
 
-        let info = mock_info("nobody", &[]);
-        //try Cancel() sender "nobody" operation_id "3" admin "creator" proposers "prop1, prop2"
+        //the revived operation waits the minimum delay again
         let res =
-            execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(3)).unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::Unexpired {});
+        env.block.time = env.block.time.plus_seconds(10);
+        let res = execute_veto(deps.as_mut(), env.clone(), guardian, Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::VetoWindowClosed {});
+        execute_execute::<Empty>(deps.as_mut(), env, info, Uint64::new(1)).unwrap();
     }
 
     #[test]
-    fn test_add_remove_proposer() {
+    fn test_veto_approvals() {
         let mut deps = mock_dependencies();
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
+        let env = mock_env();
         let msg = InstantiateMsg {
-            admins: Option::None,
-            proposers: vec![],
+            admins: Option::Some(vec![
+                "admin1".to_string(),
+                "admin2".to_string(),
+                "admin3".to_string(),
+            ]),
+            proposers: vec!["prop1".to_string()],
             min_delay: Duration::Time(10),
+            limits: Option::None,
         };
         let info = mock_info("creator", &[]);
 
         // instantiate
-        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        println!("{:?}", res);
-
-        //try remove_proposer sender "creator" proposer_address "prop1" proposers ""
-        let res = execute_remove_proposer(
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let admin1 = mock_info("admin1", &[]);
+        let admin2 = mock_info("admin2", &[]);
+        execute_add_guardian(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            "prop1".to_string(),
-        )
-        .unwrap_err();
-        assert_eq!(
-            res,
-            ContractError::NotFound {
-                address: "prop1".to_string()
-            }
-        );
-
-        let info = mock_info("no_admin", &[]);
-        //try remove_proposer sender "no_admin" proposer_address "prop1" proposers ""
-        let res = execute_remove_proposer(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "prop1".to_string(),
+            admin1.clone(),
+            "guardian".to_string(),
         )
-        .unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
-
-        //try add_proposer sender "no_admin" proposer_address "prop1" proposers ""
-        let res = execute_add_proposer(
+        .unwrap();
+        execute_update_expedite_policy(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            "prop1".to_string(),
+            admin1.clone(),
+            Some(ExpeditePolicy {
+                min_delay: Duration::Time(5),
+                quorum: 2,
+            }),
         )
-        .unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+        .unwrap();
 
-        let info = mock_info("creator", &[]);
-        //add_proposer sender "creator" proposer_address "prop1" proposers ""
-        let res = execute_add_proposer(
+        let info = mock_info("prop1", &[]);
+        for _ in 0..2 {
+            execute_schedule(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                to_binary(&"data").unwrap(),
+                "Title Example ".to_string(),
+                "test desc".to_string(),
+                Scheduled::AtTime(env.block.time.plus_seconds(10)),
+                Option::None,
+                Option::None,
+                Option::None,
+            )
+            .unwrap();
+        }
+        execute_veto(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            "prop1".to_string(),
+            mock_info("guardian", &[]),
+            Uint64::new(1),
         )
         .unwrap();
-        println!("{:?}", res);
+        execute_approve_revival(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1))
+            .unwrap();
+        execute_approve_expedite(deps.as_mut(), env.clone(), admin1, Uint64::new(2)).unwrap();
 
-        //remove_proposer sender "no_admin" proposer_address "prop1" proposers "prop1"
-        let res = execute_remove_proposer(
+        //try Amend() the vetoed payload whose revival is being approved
+        for sender in [info, admin2.clone()] {
+            let res = execute_amend::<Empty>(
+                deps.as_mut(),
+                env.clone(),
+                sender,
+                Uint64::new(1),
+                Option::Some(to_binary(&"other data").unwrap()),
+                Option::None,
+                Option::None,
+                Option::None,
+            );
+            assert_eq!(res.unwrap_err(), ContractError::Vetoed {});
+        }
+        let operation = OPERATION_LIST.load(&deps.storage, 1u64).unwrap();
+        assert_eq!(operation.data, to_binary(&"data").unwrap());
+
+        //approvals of a revoked admin do not count
+        execute_revoke_admin(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            "prop1".to_string(),
+            mock_info("admin3", &[]),
+            "admin1".to_string(),
         )
         .unwrap();
-        println!("{:?}", res);
+        assert!(query_get_revival_approvals(deps.as_ref(), Uint64::new(1))
+            .unwrap()
+            .is_empty());
+        let res =
+            execute_approve_revival(deps.as_mut(), env.clone(), admin2.clone(), Uint64::new(1))
+                .unwrap();
+        assert_eq!(attribute(&res.events[0], "approvals"), "1");
+        assert_eq!(
+            query_get_operation_status(deps.as_ref(), Uint64::new(1)).unwrap(),
+            OperationStatus::Vetoed
+        );
+        let res =
+            execute_approve_expedite(deps.as_mut(), env.clone(), admin2, Uint64::new(2)).unwrap();
+        assert_eq!(attribute(&res.events[0], "approvals"), "1");
+        let operation = OPERATION_LIST.load(&deps.storage, 2u64).unwrap();
+        assert!(!operation.expedited);
+
+        let admin3 = mock_info("admin3", &[]);
+        execute_approve_revival(deps.as_mut(), env.clone(), admin3.clone(), Uint64::new(1))
+            .unwrap();
+        assert_eq!(
+            query_get_operation_status(deps.as_ref(), Uint64::new(1)).unwrap(),
+            OperationStatus::Pending
+        );
+        execute_approve_expedite(deps.as_mut(), env, admin3, Uint64::new(2)).unwrap();
+        let operation = OPERATION_LIST.load(&deps.storage, 2u64).unwrap();
+        assert!(operation.expedited);
     }
 
     #[test]
-    fn test_update_min_delay() {
+    fn test_expedite() {
         let mut deps = mock_dependencies();
         let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
         let msg = InstantiateMsg {
-            admins: Option::None,
-            proposers: vec![],
-            min_delay: Duration::Time(10),
+            admins: Option::Some(vec!["admin1".to_string(), "admin2".to_string()]),
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(100),
+            limits: Option::None,
         };
         let info = mock_info("creator", &[]);
 
         // instantiate
-        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        println!("{:?}", res);
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        //update_min_delay() sender "creator"
-        let res = execute_update_min_delay(
+        let info = mock_info("prop1", &[]);
+        execute_schedule(
             deps.as_mut(),
             env.clone(),
             info.clone(),
-            Duration::Time(100),
+            "target".to_string(),
+            to_binary(&"data").unwrap(),
+            "Title Example ".to_string(),
+            "test desc".to_string(),
+            Scheduled::AtTime(env.block.time.plus_seconds(100)),
+            Option::None,
+            Option::None,
+            Option::None,
         )
         .unwrap();
-        println!("{:?}", res);
 
-        let info = mock_info("no_admin", &[]);
-        //try update_min_delay() sender "no_admin"
-        let res = execute_update_min_delay(
+        let admin1 = mock_info("admin1", &[]);
+        let res =
+            execute_approve_expedite(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::ExpediteDisabled {});
+
+        let policy = ExpeditePolicy {
+            min_delay: Duration::Time(10),
+            quorum: 2,
+        };
+        let res = execute_update_expedite_policy(
+            deps.as_mut(),
+            env.clone(),
+            admin1.clone(),
+            Some(ExpeditePolicy {
+                quorum: 0,
+                ..policy.clone()
+            }),
+        );
+        assert_eq!(res.unwrap_err(), ContractError::InvalidExpeditePolicy {});
+        for min_delay in [Duration::Time(101), Duration::Height(10)] {
+            let res = execute_update_expedite_policy(
+                deps.as_mut(),
+                env.clone(),
+                admin1.clone(),
+                Some(ExpeditePolicy {
+                    min_delay,
+                    ..policy.clone()
+                }),
+            );
+            assert_eq!(res.unwrap_err(), ContractError::InvalidExpeditePolicy {});
+        }
+        execute_update_expedite_policy(
+            deps.as_mut(),
+            env.clone(),
+            admin1.clone(),
+            Some(policy.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            query_get_expedite_policy(deps.as_ref()).unwrap(),
+            Some(policy)
+        );
+
+        //only admins approve, each once
+        let res =
+            execute_approve_expedite(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res =
+            execute_approve_expedite(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1))
+                .unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(attribute(&res.events[0], "action"), "approve_expedite");
+        assert_eq!(attribute(&res.events[0], "approvals"), "1");
+        let res =
+            execute_approve_expedite(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::AlreadyApproved {});
+
+        //changing the payload drops the approvals
+        execute_amend::<Empty>(
             deps.as_mut(),
             env.clone(),
             info.clone(),
-            Duration::Time(100),
+            Uint64::new(1),
+            Option::Some(to_binary(&"other data").unwrap()),
+            Option::None,
+            Option::None,
+            Option::None,
         )
-        .unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+        .unwrap();
+        assert!(query_get_expedite_approvals(deps.as_ref(), Uint64::new(1))
+            .unwrap()
+            .is_empty());
+
+        execute_approve_expedite(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1))
+            .unwrap();
+        let res = execute_approve_expedite(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin2", &[]),
+            Uint64::new(1),
+        )
+        .unwrap();
+        assert_eq!(attribute(&res.events[1], "action"), "expedite");
+        let operation = OPERATION_LIST.load(&deps.storage, 1).unwrap();
+        assert!(operation.expedited);
+        assert_eq!(
+            operation.execution_time,
+            Scheduled::AtTime(env.block.time.plus_seconds(10))
+        );
+        let res = query_get_operations(deps.as_ref(), None, None, None).unwrap();
+        assert!(res.operationList[0].expedited);
+        let res =
+            execute_approve_expedite(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::NotExpeditable {});
+
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1));
+        assert_eq!(res.unwrap_err(), ContractError::Unexpired {});
+        env.block.time = env.block.time.plus_seconds(10);
+        let res =
+            execute_execute::<Empty>(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1))
+                .unwrap();
+        assert_eq!(attribute(&res.events[0], "action"), "execute");
+        assert_eq!(attribute(&res.events[0], "expedited"), "true");
+
+        //cancelling and vetoing drop the approvals
+        execute_add_guardian(
+            deps.as_mut(),
+            env.clone(),
+            admin1.clone(),
+            "guardian".to_string(),
+        )
+        .unwrap();
+        for id in 2..4 {
+            execute_schedule(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                to_binary(&"data").unwrap(),
+                "Title Example ".to_string(),
+                "test desc".to_string(),
+                Scheduled::AtTime(env.block.time.plus_seconds(100)),
+                Option::None,
+                Option::None,
+                Option::None,
+            )
+            .unwrap();
+            execute_approve_expedite(deps.as_mut(), env.clone(), admin1.clone(), Uint64::new(id))
+                .unwrap();
+        }
+        execute_cancel(deps.as_mut(), env.clone(), info, Uint64::new(2)).unwrap();
+        assert!(!EXPEDITE_APPROVALS.has(&deps.storage, (2, &Addr::unchecked("admin1"))));
+        execute_veto(
+            deps.as_mut(),
+            env,
+            mock_info("guardian", &[]),
+            Uint64::new(3),
+        )
+        .unwrap();
+        assert!(!EXPEDITE_APPROVALS.has(&deps.storage, (3, &Addr::unchecked("admin1"))));
     }
 
     #[test]
-    fn test_revoke_admin() {
+    fn test_events() {
         let mut deps = mock_dependencies();
         let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
         let msg = InstantiateMsg {
             admins: Option::None,
-            proposers: vec![],
+            proposers: vec!["prop1".to_string(), "prop2".to_string()],
             min_delay: Duration::Time(10),
+            limits: Option::None,
         };
         let info = mock_info("creator", &[]);
 
         // instantiate
         let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        println!("{:?}", res);
-
-        //try revoke_admin() sender "creator" admin_address "not_in_it" admin "creator"
-        let res = execute_revoke_admin(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "not_in_it".to_string(),
-        )
-        .unwrap_err();
+        assert!(res.attributes.is_empty());
+        assert_eq!(res.events.len(), 1);
+        let event = &res.events[0];
+        assert_eq!(attribute(event, "action"), "instantiate");
+        assert_eq!(attribute(event, "sender"), "creator");
         assert_eq!(
-            res,
-            ContractError::NotFound {
-                address: "not_in_it".to_string()
-            }
+            attribute(event, "admins"),
+            format!("creator,{}", env.contract.address)
         );
+        assert_eq!(attribute(event, "proposers"), "prop1,prop2");
+        assert_eq!(attribute(event, "min_delay_seconds"), "10");
 
-        //revoke_admin() sender "creator" admin_address "creator" admin "creator"
-        let res = execute_revoke_admin(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "creator".to_string(),
-        )
-        .unwrap();
-        println!("{:?}", res);
+        let res =
+            execute_add_proposer(deps.as_mut(), env.clone(), info, "prop3".to_string()).unwrap();
+        assert_eq!(attribute(&res.events[0], "action"), "add_proposer");
+        assert_eq!(attribute(&res.events[0], "proposer"), "prop3");
 
-        //try revoke_admin() sender "creator" admin_address "creator" admin ""
-        let res = execute_revoke_admin(
-            deps.as_mut(),
-            env.clone(),
-            info.clone(),
-            "creator".to_string(),
-        )
-        .unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+        let info = mock_info("prop1", &[]);
+        let execution_time = env.block.time.plus_seconds(10);
+        for _ in 0..2 {
+            let res = execute_schedule(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                to_binary(&"data").unwrap(),
+                "Title Example ".to_string(),
+                "test desc".to_string(),
+                Scheduled::AtTime(execution_time),
+                Option::None,
+                Option::None,
+                Option::None,
+            )
+            .unwrap();
+            assert!(res.attributes.is_empty());
+            let event = &res.events[0];
+            assert_eq!(attribute(event, "action"), "schedule");
+            assert_eq!(attribute(event, "proposer"), "prop1");
+            assert_eq!(attribute(event, "target"), "target");
+            assert_eq!(attribute(event, "status"), "pending");
+            assert_eq!(
+                attribute(event, "execution_time"),
+                execution_time.nanos().to_string()
+            );
+        }
+
+        let res = execute_cancel(deps.as_mut(), env.clone(), info.clone(), Uint64::new(2)).unwrap();
+        let event = &res.events[0];
+        assert_eq!(attribute(event, "action"), "cancel");
+        assert_eq!(attribute(event, "operation_id"), "2");
+        assert_eq!(attribute(event, "status"), "cancelled");
+
+        env.block.time = execution_time;
+        let res = execute_execute::<Empty>(deps.as_mut(), env, info, Uint64::new(1)).unwrap();
+        assert!(res.attributes.is_empty());
+        let event = &res.events[0];
+        assert_eq!(attribute(event, "action"), "execute");
+        assert_eq!(attribute(event, "operation_id"), "1");
+        assert_eq!(attribute(event, "status"), "done");
+        assert_eq!(attribute(event, "executor"), "prop1");
+        assert_eq!(attribute(event, "execution_count"), "1");
+        assert_eq!(attribute(event, "expedited"), "false");
     }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -29,6 +29,93 @@ pub enum ContractError {
 
     #[error("Changes can not be made on a frozen Timelock contract.")]
     TimelockFrozen {},
+
+    #[error(
+        "Recurrence interval and end must be non-zero and of the same kind as the execution time."
+    )]
+    InvalidRecurrence {},
+
+    #[error("Timelock holds {balance} of cw20 token {token}, operation needs {amount}.")]
+    InsufficientCw20Balance {
+        token: String,
+        balance: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Timelock has {available}{denom} not reserved for vesting, operation needs {amount}.")]
+    InsufficientNativeBalance {
+        denom: String,
+        available: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Vesting stream needs a non-zero total and start <= cliff <= end with start < end.")]
+    InvalidVestingSchedule {},
+
+    #[error("Insufficient {denom} balance to create the vesting stream.")]
+    InsufficientBalance { denom: String },
+
+    #[error("Nothing to claim yet.")]
+    NothingToClaim {},
+
+    #[error("Hook {address:?} is already registered")]
+    HookAlreadyRegistered { address: String },
+
+    #[error("Title is {length} bytes long, the maximum is {max}.")]
+    TitleTooLong { length: usize, max: usize },
+
+    #[error("Description is {length} bytes long, the maximum is {max}.")]
+    DescriptionTooLong { length: usize, max: usize },
+
+    #[error("Payload is {length} bytes long, the maximum is {max}.")]
+    PayloadTooLarge { length: usize, max: usize },
+
+    #[error("Proposer already has {max} pending operations.")]
+    TooManyPendingOperations { max: u32 },
+
+    #[error("Proposer already scheduled {max} operations in the current {window_seconds} second window.")]
+    ScheduleRateLimited { max: u32, window_seconds: u64 },
+
+    #[error("A rate limit needs a non-zero window.")]
+    InvalidProposerLimits {},
+
+    #[error("Guardians list already contains this guardian address")]
+    AlreadyContainsGuardianAddress {},
+
+    #[error("Operations can only be vetoed while they are pending and their delay is not over.")]
+    VetoWindowClosed {},
+
+    #[error("This operation is vetoed.")]
+    Vetoed {},
+
+    #[error("Only vetoed operations can be revived.")]
+    NotVetoed {},
+
+    #[error("Sender already approved reviving this operation.")]
+    AlreadyApproved {},
+
+    #[error("Expedited execution is disabled.")]
+    ExpediteDisabled {},
+
+    #[error(
+        "Expedite quorum must be at least one and its delay no longer than the minimum delay."
+    )]
+    InvalidExpeditePolicy {},
+
+    #[error("Only pending operations can be expedited.")]
+    NotExpeditable {},
+
+    #[error("Content hash must be a hex encoded sha256 hash.")]
+    InvalidContentHash {},
+
+    #[error("Metadata takes at most {max} urls and {max} tags, none of them empty or duplicated.")]
+    InvalidMetadata { max: usize },
+
+    #[error("Metadata entry is {length} bytes long, the maximum is {max}.")]
+    MetadataEntryTooLong { length: usize, max: usize },
+
+    #[error("Cannot migrate from {contract} version {version}.")]
+    CannotMigrate { contract: String, version: String },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -1,5 +1,11 @@
-use crate::state::{Operation, OperationStatus};
-use cosmwasm_std::{Addr, Binary, Uint64};
+use crate::state::{
+    ConfigChange, ExpeditePolicy, HookFailurePolicy, InputLimits, Operation, OperationMetadata,
+    OperationStatus, PayloadKind, ProposerLimits, Recurrence, VestingAsset, VestingStream,
+};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CosmosMsg, Empty, StdResult, Timestamp, Uint128, Uint64, WasmMsg,
+};
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
 use cw_utils::{Duration, Scheduled};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -9,11 +15,13 @@ pub struct InstantiateMsg {
     pub admins: Option<Vec<String>>,
     pub proposers: Vec<String>,
     pub min_delay: Duration,
+    /// defaults to InputLimits::default()
+    pub limits: Option<InputLimits>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum ExecuteMsg {
+pub enum ExecuteMsg<C = Empty> {
     Schedule {
         target_address: String,
         data: Binary,
@@ -21,6 +29,27 @@ pub enum ExecuteMsg {
         description: String,
         execution_time: Scheduled,
         executors: Option<Vec<String>>,
+        recurrence: Option<Recurrence>,
+        metadata: Option<OperationMetadata>,
+    },
+
+    /// Schedules any CosmosMsg, including the custom messages of the chain the timelock is built for
+    ScheduleMsg {
+        msg: CosmosMsg<C>,
+        title: String,
+        description: String,
+        execution_time: Scheduled,
+        executors: Option<Vec<String>>,
+        recurrence: Option<Recurrence>,
+        metadata: Option<OperationMetadata>,
+    },
+
+    Amend {
+        operation_id: Uint64,
+        data: Option<Binary>,
+        title: Option<String>,
+        description: Option<String>,
+        execution_time: Option<Scheduled>,
     },
 
     Cancel {
@@ -46,7 +75,101 @@ pub enum ExecuteMsg {
     UpdateMinDelay {
         new_delay: Duration,
     },
+
+    AddGuardian {
+        guardian_address: String,
+    },
+
+    RemoveGuardian {
+        guardian_address: String,
+    },
+
+    /// guardians veto a pending operation until its execution time
+    Veto {
+        operation_id: Uint64,
+    },
+
+    /// admin approval to revive a vetoed operation, the operation restarts the minimum delay
+    /// once a two thirds supermajority of the admins approved it
+    ApproveRevival {
+        operation_id: Uint64,
+    },
+
+    /// admin approval to expedite a pending operation, once the quorum of the expedite policy
+    /// approved it the operation can be executed after the expedited minimum delay
+    ApproveExpedite {
+        operation_id: Uint64,
+    },
+
+    UpdateExpeditePolicy {
+        policy: Option<ExpeditePolicy>,
+    },
+
+    UpdateInputLimits {
+        limits: InputLimits,
+    },
+
+    UpdateProposerLimits {
+        limits: ProposerLimits,
+    },
+
     Freeze {},
+
+    /// Records cw20 deposits to the timelock treasury
+    Receive(Cw20ReceiveMsg),
+
+    /// Schedules a cw20 Transfer of tracked treasury tokens
+    ScheduleCw20Transfer {
+        token_address: String,
+        recipient: String,
+        amount: Uint128,
+        title: String,
+        description: String,
+        execution_time: Scheduled,
+        executors: Option<Vec<String>>,
+    },
+
+    /// Schedules a cw20 Send of tracked treasury tokens
+    ScheduleCw20Send {
+        token_address: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+        title: String,
+        description: String,
+        execution_time: Scheduled,
+        executors: Option<Vec<String>>,
+    },
+
+    /// Can only be called by the timelock itself, through a scheduled operation
+    CreateVestingStream {
+        recipient: String,
+        asset: VestingAssetMsg,
+        total: Uint128,
+        start: Timestamp,
+        cliff: Timestamp,
+        end: Timestamp,
+    },
+
+    ClaimVesting {
+        stream_id: u64,
+    },
+
+    AddHook {
+        addr: String,
+        on_failure: HookFailurePolicy,
+    },
+
+    RemoveHook {
+        addr: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VestingAssetMsg {
+    Native { denom: String },
+    Cw20 { address: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -60,20 +183,132 @@ pub enum QueryMsg {
         operation_id: Uint64,
     },
 
+    /// Lists all admins, prefer the paginated ListAdmins
     GetAdmins {},
 
     GetOperations {
         start_after: Option<u64>,
         limit: Option<u32>,
+        /// leaves data and description empty to keep pages small
+        omit_large_fields: Option<bool>,
+    },
+
+    GetOperationsByTag {
+        tag: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        omit_large_fields: Option<bool>,
     },
 
     GetMinDelay {},
 
+    GetInputLimits {},
+
+    GetProposerLimits {},
+
+    GetProposerUsage {
+        proposer: String,
+    },
+
+    /// Lists all proposers, prefer the paginated ListProposers
     GetProposers {},
 
     GetExecutors {
         operation_id: Uint64,
     },
+
+    GetOperationHistory {
+        operation_id: Uint64,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+        omit_large_fields: Option<bool>,
+    },
+
+    GetCw20Balances {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    GetVestingStream {
+        stream_id: u64,
+    },
+
+    GetVestingStreams {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    GetHooks {},
+
+    GetConfigHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    ListAdmins {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    ListProposers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    IsAdmin {
+        address: String,
+    },
+
+    IsProposer {
+        address: String,
+    },
+
+    ListGuardians {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    IsGuardian {
+        address: String,
+    },
+
+    GetRevivalApprovals {
+        operation_id: Uint64,
+    },
+
+    GetExpeditePolicy {},
+
+    GetExpediteApprovals {
+        operation_id: Uint64,
+    },
+
+    /// Dry-runs the checks a pending operation has to pass on execution
+    SimulateOperation {
+        operation_id: Uint64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+/// SudoMsg lets chain governance intervene in a misconfigured or captured timelock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    ForceCancel {
+        operation_id: Uint64,
+    },
+
+    ReplaceRoles {
+        admins: Option<Vec<String>>,
+        proposers: Option<Vec<String>>,
+    },
+
+    Unfreeze {},
+
+    UpdateMinDelay {
+        new_delay: Duration,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -82,11 +317,30 @@ pub struct OperationResponse {
     pub status: OperationStatus,
     pub proposer: Addr,
     pub executors: Option<Vec<Addr>>,
+    /// for recurring operations this is the next trigger time
     pub execution_time: Scheduled,
     pub target: Addr,
     pub data: Binary,
+    pub payload_kind: PayloadKind,
     pub title: String,
     pub description: String,
+    pub revision: u32,
+    pub recurrence: Option<Recurrence>,
+    pub execution_count: u32,
+    pub metadata: Option<OperationMetadata>,
+    pub expedited: bool,
+}
+
+impl OperationResponse {
+    /// empties the fields that can get large, data, description and metadata
+    pub fn without_large_fields(self) -> OperationResponse {
+        OperationResponse {
+            data: Binary::default(),
+            description: String::new(),
+            metadata: None,
+            ..self
+        }
+    }
 }
 
 //impl Into<OperationResponse> for Operation changed to from due to lint warning
@@ -100,8 +354,14 @@ impl From<Operation> for OperationResponse {
             execution_time: operation.execution_time,
             target: operation.target,
             data: operation.data,
+            payload_kind: operation.payload_kind,
             title: operation.title,
             description: operation.description,
+            revision: operation.revision,
+            recurrence: operation.recurrence,
+            execution_count: operation.execution_count,
+            metadata: operation.metadata,
+            expedited: operation.expedited,
         }
     }
 }
@@ -112,3 +372,132 @@ impl From<Operation> for OperationResponse {
 pub struct OperationListResponse {
     pub operationList: Vec<OperationResponse>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20BalancesResponse {
+    pub balances: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingStreamResponse {
+    pub id: u64,
+    pub recipient: Addr,
+    pub asset: VestingAsset,
+    pub total: Uint128,
+    pub start: Timestamp,
+    pub cliff: Timestamp,
+    pub end: Timestamp,
+    pub vested: Uint128,
+    pub claimed: Uint128,
+    /// total amount not claimed yet
+    pub remaining: Uint128,
+}
+
+impl VestingStreamResponse {
+    pub fn new(stream: VestingStream, now: Timestamp) -> VestingStreamResponse {
+        VestingStreamResponse {
+            vested: stream.vested(now),
+            remaining: stream.total - stream.claimed,
+            id: stream.id,
+            recipient: stream.recipient,
+            asset: stream.asset,
+            total: stream.total,
+            start: stream.start,
+            cliff: stream.cliff,
+            end: stream.end,
+            claimed: stream.claimed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingStreamListResponse {
+    pub streams: Vec<VestingStreamResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookResponse {
+    pub addr: Addr,
+    pub on_failure: HookFailurePolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<HookResponse>,
+}
+
+/// OperationHookMsg is sent to every subscriber when an operation is scheduled, cancelled or executed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationHookMsg {
+    Scheduled {
+        operation_id: Uint64,
+        proposer: Addr,
+        target: Addr,
+        execution_time: Scheduled,
+    },
+    Cancelled {
+        operation_id: Uint64,
+        /// None when force-cancelled by chain governance
+        sender: Option<Addr>,
+    },
+    Executed {
+        operation_id: Uint64,
+        executor: Addr,
+    },
+    Vetoed {
+        operation_id: Uint64,
+        guardian: Addr,
+    },
+    Revived {
+        operation_id: Uint64,
+        execution_time: Scheduled,
+    },
+}
+
+impl OperationHookMsg {
+    /// serializes the message wrapped in OperationHookExecuteMsg
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = OperationHookExecuteMsg::OperationHook(self);
+        to_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// OperationHookExecuteMsg is the execute message subscribers have to handle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationHookExecuteMsg {
+    OperationHook(OperationHookMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigHistoryResponse {
+    pub changes: Vec<ConfigChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposerUsageResponse {
+    pub proposer: Addr,
+    pub pending: u32,
+    /// operations scheduled in the current window, zero once the window is over
+    pub scheduled_in_window: u32,
+    pub window_start: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateOperationResponse {
+    pub operation_id: Uint64,
+    /// empty if no problem was found
+    pub warnings: Vec<String>,
+}
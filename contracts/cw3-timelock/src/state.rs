@@ -1,16 +1,77 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-use cosmwasm_std::{Addr, Binary, Uint64};
+use cosmwasm_std::{Addr, Binary, Empty, Timestamp, Uint128, Uint64};
 use cw_storage_plus::{Item, Map};
 use cw_utils::{Duration, Scheduled};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Timelock {
-    pub admins: Vec<Addr>,
-    pub proposers: Vec<Addr>,
     pub min_time_delay: Duration,
     pub frozen: bool,
+    /// configs stored before the limits existed get the defaults
+    #[serde(default)]
+    pub limits: InputLimits,
+    #[serde(default)]
+    pub proposer_limits: ProposerLimits,
+    /// None disables expedited execution
+    #[serde(default)]
+    pub expedite_policy: Option<ExpeditePolicy>,
+}
+
+/// ExpeditePolicy lets a quorum of admins shorten the delay of an operation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExpeditePolicy {
+    pub min_delay: Duration,
+    /// number of admin approvals needed to expedite an operation
+    pub quorum: u32,
+}
+
+/// InputLimits bounds the size of what proposers can store with an operation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InputLimits {
+    pub max_title_length: u32,
+    pub max_description_length: u32,
+    /// maximum size of the operation payload in bytes
+    pub max_data_length: u32,
+    /// maximum length of a metadata url or tag, tags are also storage keys
+    #[serde(default = "default_max_metadata_entry_length")]
+    pub max_metadata_entry_length: u32,
+}
+
+fn default_max_metadata_entry_length() -> u32 {
+    256
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        InputLimits {
+            max_title_length: 256,
+            max_description_length: 4096,
+            max_data_length: 16384,
+            max_metadata_entry_length: default_max_metadata_entry_length(),
+        }
+    }
+}
+
+/// ProposerLimits caps the operations a single proposer can schedule, no limit by default
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ProposerLimits {
+    /// maximum number of pending operations per proposer
+    pub max_pending: Option<u32>,
+    /// maximum number of operations a proposer can schedule per window
+    pub max_per_window: Option<u32>,
+    /// length of the rate limit window in seconds
+    pub window_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ProposerUsage {
+    /// operations of the proposer that are neither executed nor cancelled
+    pub pending: u32,
+    pub window_start: Timestamp,
+    pub scheduled_in_window: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -22,8 +83,54 @@ pub struct Operation {
     pub execution_time: Scheduled,
     pub target: Addr,
     pub data: Binary,
+    #[serde(default)]
+    pub payload_kind: PayloadKind,
     pub title: String,
     pub description: String,
+    /// number of times the operation has been amended
+    #[serde(default)]
+    pub revision: u32,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// number of times the operation has been executed
+    #[serde(default)]
+    pub execution_count: u32,
+    #[serde(default)]
+    pub metadata: Option<OperationMetadata>,
+    /// set once an admin quorum approved the shorter expedited delay
+    #[serde(default)]
+    pub expedited: bool,
+}
+
+/// OperationMetadata pins the off-chain documents an operation was discussed in
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct OperationMetadata {
+    /// forum threads, IPFS gateways and similar links
+    pub urls: Vec<String>,
+    /// hex encoded sha256 hash of the proposal document
+    pub content_hash: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// PayloadKind tells how the operation data is dispatched on execution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadKind {
+    /// data is the execute message sent to the target contract
+    #[default]
+    WasmExecute,
+    /// data is a serialized CosmosMsg, possibly carrying a chain-specific custom message
+    CosmosMsg,
+}
+
+/// Recurrence makes an operation executable again every `interval` after its execution time,
+/// until `end` is reached or it has been executed `max_executions` times.
+/// Periods missed by a late execution are skipped
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Recurrence {
+    pub interval: Duration,
+    pub end: Option<Scheduled>,
+    pub max_executions: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -31,8 +138,106 @@ pub enum OperationStatus {
     Pending,
     Ready,
     Done,
+    /// force-cancelled by chain governance, only found in the operation history
+    Cancelled,
+    /// vetoed by a guardian, can only be revived by an admin supermajority
+    Vetoed,
+}
+
+impl fmt::Display for OperationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OperationStatus::Pending => write!(f, "pending"),
+            OperationStatus::Ready => write!(f, "ready"),
+            OperationStatus::Done => write!(f, "done"),
+            OperationStatus::Cancelled => write!(f, "cancelled"),
+            OperationStatus::Vetoed => write!(f, "vetoed"),
+        }
+    }
 }
 
 pub const CONFIG: Item<Timelock> = Item::new("timelock");
+/// role members are kept out of CONFIG, so loading it does not grow with their number
+pub const ADMINS: Map<&Addr, Empty> = Map::new("admins");
+pub const PROPOSERS: Map<&Addr, Empty> = Map::new("proposers");
+pub const GUARDIANS: Map<&Addr, Empty> = Map::new("guardians");
+// admins that approved reviving a vetoed operation
+pub const REVIVAL_APPROVALS: Map<(u64, &Addr), Empty> = Map::new("revival_approvals");
+// admins that approved expediting an operation
+pub const EXPEDITE_APPROVALS: Map<(u64, &Addr), Empty> = Map::new("expedite_approvals");
+pub const PROPOSER_USAGE: Map<&Addr, ProposerUsage> = Map::new("proposer_usage");
+
+/// ConfigChange records a change made through sudo, with the config as it was before
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigChange {
+    pub action: String,
+    pub height: u64,
+    pub time: Timestamp,
+    pub previous: Timelock,
+    /// set when the change replaced the admins
+    pub previous_admins: Option<Vec<Addr>>,
+    /// set when the change replaced the proposers
+    pub previous_proposers: Option<Vec<Addr>>,
+}
+
+pub const CONFIG_HISTORY: Map<u64, ConfigChange> = Map::new("config_history");
+pub const CONFIG_HISTORY_SEQ: Item<u64> = Item::new("config_history_seq");
 pub const OPERATION_LIST: Map<u64, Operation> = Map::new("operation_list");
 pub const OPERATION_SEQ: Item<Uint64> = Item::new("operation_seq");
+// index of the operations carrying a tag
+pub const OPERATION_TAGS: Map<(&str, u64), Empty> = Map::new("operation_tags");
+
+/// OPERATION_HISTORY: (operation_id, revision) -> Operation as it was before being amended
+pub const OPERATION_HISTORY: Map<(u64, u32), Operation> = Map::new("operation_history");
+/// CW20_BALANCES: cw20 token address -> amount deposited through Receive and not yet sent out
+pub const CW20_BALANCES: Map<&Addr, Uint128> = Map::new("cw20_balances");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VestingAsset {
+    Native { denom: String },
+    Cw20 { address: Addr },
+}
+
+/// VestingStream releases `total` linearly between `start` and `end`, nothing is claimable before `cliff`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingStream {
+    pub id: u64,
+    pub recipient: Addr,
+    pub asset: VestingAsset,
+    pub total: Uint128,
+    pub claimed: Uint128,
+    pub start: Timestamp,
+    pub cliff: Timestamp,
+    pub end: Timestamp,
+}
+
+impl VestingStream {
+    pub fn vested(&self, now: Timestamp) -> Uint128 {
+        if now < self.cliff {
+            return Uint128::zero();
+        }
+        if now >= self.end {
+            return self.total;
+        }
+        let elapsed = now.seconds() - self.start.seconds();
+        let duration = self.end.seconds() - self.start.seconds();
+        self.total.multiply_ratio(elapsed, duration)
+    }
+}
+
+pub const VESTING_STREAMS: Map<u64, VestingStream> = Map::new("vesting_streams");
+pub const VESTING_SEQ: Item<u64> = Item::new("vesting_seq");
+/// VESTING_RESERVED: native denom -> amount locked in vesting streams and not claimed yet
+pub const VESTING_RESERVED: Map<&str, Uint128> = Map::new("vesting_reserved");
+
+/// HookFailurePolicy decides whether a failing subscriber reverts the operation lifecycle call
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookFailurePolicy {
+    Ignore,
+    Revert,
+}
+
+/// HOOKS: subscriber contract -> failure policy
+pub const HOOKS: Map<&Addr, HookFailurePolicy> = Map::new("hooks");
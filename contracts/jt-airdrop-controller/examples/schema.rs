@@ -4,7 +4,8 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use jt_airdrop_controller::msg::{
-    ConfigResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, ListEscrowsResponse, QueryMsg,
+    ConfigResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, ListDelegatesResponse,
+    ListEscrowsResponse, MigrateMsg, QueryMsg, ReceiveMsg,
 };
 use jt_airdrop_controller::state::{Config, Escrow};
 
@@ -17,9 +18,12 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(EscrowResponse), &out_dir);
     export_schema(&schema_for!(ListEscrowsResponse), &out_dir);
+    export_schema(&schema_for!(ListDelegatesResponse), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(Escrow), &out_dir);
 }
@@ -1,26 +1,41 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
-    QueryRequest, Response, StdResult, Uint128, Uint64, WasmQuery,
+    from_binary, to_binary, Addr, BankMsg, Binary, Coin, ContractInfoResponse, CosmosMsg, Deps,
+    DepsMut, Empty, Env, Event, MessageInfo, Order, QueryRequest, Response, StdResult, Uint128,
+    Uint64, WasmMsg, WasmQuery,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-use cw20_merkle_airdrop::msg::LatestStageResponse;
-use cw20_merkle_airdrop::msg::QueryMsg::LatestStage;
-use cw_storage_plus::Bound;
+use cw20_merkle_airdrop::msg::QueryMsg::{
+    Config as AirdropConfig, LatestStage, MerkleRoot, TotalClaimed,
+};
+use cw20_merkle_airdrop::msg::{
+    ConfigResponse as AirdropConfigResponse, LatestStageResponse, MerkleRootResponse,
+    TotalClaimedResponse,
+};
+use cw_storage_plus::{Bound, Item, Map};
 use cw_utils::{Expiration, NativeBalance};
+use std::collections::BTreeMap;
 
 use crate::error::ContractError;
 
 use crate::msg::{
-    ConfigResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, ListEscrowsResponse, QueryMsg,
+    ConfigResponse, EscrowResponse, ExecuteMsg, InstantiateMsg, ListDelegatesResponse,
+    ListEscrowsResponse, MigrateMsg, QueryMsg, ReceiveMsg,
 };
-use crate::state::{Config, Escrow, CONFIG, ESCROWS};
+use crate::state::{
+    AssetType, Config, Escrow, ReleasePolicy, ReleaseSplits, AIRDROP_CODE_IDS, CONFIG, CW20_ASSETS,
+    DELEGATES, ESCROWS, NATIVE_ASSETS, UNRELEASED_ESCROWS,
+};
+use serde::{Deserialize, Serialize};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:jt-airdrop-controller";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// the version storing the legacy config and escrows migrate rewrites
+const PREVIOUS_VERSION: &str = "0.2.0";
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -38,22 +53,34 @@ pub fn instantiate(
         .release_addr
         .map_or(Ok(admin.clone()), |o| deps.api.addr_validate(&o))?;
 
+    let release_policy = msg.release_policy.unwrap_or_default();
+    validate_release_policy(&release_policy)?;
+    let splits = msg.splits.unwrap_or_default();
+    validate_splits(&splits)?;
+
     let config = Config {
         admin: admin.clone(),
         release_addr: release_addr.clone(),
-        escrow_amount: msg.escrow_amount,
         release_height_delta: msg.release_height_delta,
-        allowed_native: msg.allowed_native,
+        release_time_delta: msg.release_time_delta.filter(|delta| !delta.is_zero()),
+        release_policy,
+        splits,
     };
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     CONFIG.save(deps.storage, &config)?;
+    for coin in &msg.native_assets {
+        NATIVE_ASSETS.save(deps.storage, &coin.denom, &coin.amount)?;
+    }
+    for code_id in &msg.airdrop_code_ids {
+        AIRDROP_CODE_IDS.save(deps.storage, *code_id, &Empty {})?;
+    }
 
     Ok(Response::new()
         .add_attribute("action", "instantiate")
         .add_attribute("admin", admin)
         .add_attribute("release_addr", release_addr.as_str())
-        .add_attribute("escrow_amount", msg.escrow_amount))
+        .add_attribute("native_assets", join_coins(&msg.native_assets)))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -67,36 +94,66 @@ pub fn execute(
         ExecuteMsg::UpdateConfig {
             admin,
             release_addr,
-            escrow_amount,
             release_height_delta: default_release_height,
-            allowed_native,
+            release_time_delta,
+            release_policy,
+            splits,
         } => execute_update_config(
             deps,
             info,
-            env,
             admin,
             release_addr,
-            escrow_amount,
             default_release_height,
-            allowed_native,
+            release_time_delta,
+            release_policy,
+            splits,
         ),
         ExecuteMsg::ReleaseLockedFunds {
             airdrop_addr,
             stage,
         } => execute_release_funds(deps, info, env, airdrop_addr, stage),
+        ExecuteMsg::ReleaseExpired { start_after, limit } => {
+            execute_release_expired(deps, env, start_after, limit)
+        }
         ExecuteMsg::LockFunds { airdrop_addr } => execute_lock_funds(deps, info, env, airdrop_addr),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, info, env, msg),
+        ExecuteMsg::AddCw20 {
+            token_addr,
+            escrow_amount,
+        } => execute_add_cw20(deps, info, token_addr, escrow_amount),
+        ExecuteMsg::RemoveCw20 { token_addr } => execute_remove_cw20(deps, info, token_addr),
+        ExecuteMsg::AddNativeDenom {
+            denom,
+            escrow_amount,
+        } => execute_add_native_denom(deps, info, denom, escrow_amount),
+        ExecuteMsg::RemoveNativeDenom { denom } => execute_remove_native_denom(deps, info, denom),
+        ExecuteMsg::AddAirdropCodeId { code_id } => {
+            execute_add_airdrop_code_id(deps, info, code_id)
+        }
+        ExecuteMsg::RemoveAirdropCodeId { code_id } => {
+            execute_remove_airdrop_code_id(deps, info, code_id)
+        }
+        ExecuteMsg::AddDelegate {
+            airdrop_addr,
+            delegate,
+        } => execute_add_delegate(deps, info, airdrop_addr, delegate),
+        ExecuteMsg::RemoveDelegate {
+            airdrop_addr,
+            delegate,
+        } => execute_remove_delegate(deps, info, airdrop_addr, delegate),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
     info: MessageInfo,
-    _env: Env,
     admin: Option<String>,
     release_addr: Option<String>,
-    escrow_amount: Option<Uint128>,
     default_release_height: Option<Uint64>,
-    allowed_native: Option<String>,
+    release_time_delta: Option<Uint64>,
+    release_policy: Option<ReleasePolicy>,
+    splits: Option<ReleaseSplits>,
 ) -> Result<Response, ContractError> {
     // authorize owner
     let mut cfg = CONFIG.load(deps.storage)?;
@@ -110,14 +167,19 @@ pub fn execute_update_config(
     if let Some(release_addr) = release_addr {
         cfg.release_addr = deps.api.addr_validate(&release_addr)?;
     }
-    if let Some(escrow_amount) = escrow_amount {
-        cfg.escrow_amount = escrow_amount;
-    }
     if let Some(default_release_height) = default_release_height {
         cfg.release_height_delta = default_release_height;
     }
-    if let Some(allowed_native) = allowed_native {
-        cfg.allowed_native = allowed_native;
+    if let Some(release_time_delta) = release_time_delta {
+        cfg.release_time_delta = Some(release_time_delta).filter(|delta| !delta.is_zero());
+    }
+    if let Some(release_policy) = release_policy {
+        validate_release_policy(&release_policy)?;
+        cfg.release_policy = release_policy;
+    }
+    if let Some(splits) = splits {
+        validate_splits(&splits)?;
+        cfg.splits = splits;
     }
 
     CONFIG.save(deps.storage, &cfg)?;
@@ -125,23 +187,310 @@ pub fn execute_update_config(
     Ok(Response::new().add_attribute("action", "update_config"))
 }
 
+fn validate_splits(splits: &ReleaseSplits) -> Result<(), ContractError> {
+    if splits.expired_source_bps > BPS_DENOMINATOR || splits.success_fee_bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidSplits {});
+    }
+    Ok(())
+}
+
+fn validate_release_policy(policy: &ReleasePolicy) -> Result<(), ContractError> {
+    match policy {
+        // no claim at all would trivially meet the policy
+        ReleasePolicy::ClaimedRatio { min_claimed_bps }
+            if *min_claimed_bps == 0 || *min_claimed_bps > BPS_DENOMINATOR =>
+        {
+            Err(ContractError::InvalidReleasePolicy {})
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn execute_add_native_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    escrow_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    NATIVE_ASSETS.save(deps.storage, &denom, &escrow_amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_native_denom"),
+        ("denom", &denom),
+        ("escrow_amount", &escrow_amount.to_string()),
+    ]))
+}
+
+pub fn execute_remove_native_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    NATIVE_ASSETS.remove(deps.storage, &denom);
+
+    Ok(Response::new().add_attributes(vec![("action", "remove_native_denom"), ("denom", &denom)]))
+}
+
+pub fn execute_add_airdrop_code_id(
+    deps: DepsMut,
+    info: MessageInfo,
+    code_id: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    AIRDROP_CODE_IDS.save(deps.storage, code_id, &Empty {})?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_airdrop_code_id"),
+        ("code_id", &code_id.to_string()),
+    ]))
+}
+
+pub fn execute_remove_airdrop_code_id(
+    deps: DepsMut,
+    info: MessageInfo,
+    code_id: u64,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    AIRDROP_CODE_IDS.remove(deps.storage, code_id);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_airdrop_code_id"),
+        ("code_id", &code_id.to_string()),
+    ]))
+}
+
+pub fn execute_add_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    airdrop_addr: String,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let airdrop_addr = deps.api.addr_validate(&airdrop_addr)?;
+    if query_airdrop_owner(deps.as_ref(), &airdrop_addr)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let delegate = deps.api.addr_validate(&delegate)?;
+    DELEGATES.save(deps.storage, (&airdrop_addr, &delegate), &Empty {})?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_delegate"),
+        ("airdrop_addr", airdrop_addr.as_str()),
+        ("delegate", delegate.as_str()),
+    ]))
+}
+
+pub fn execute_remove_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    airdrop_addr: String,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let airdrop_addr = deps.api.addr_validate(&airdrop_addr)?;
+    if query_airdrop_owner(deps.as_ref(), &airdrop_addr)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let delegate = deps.api.addr_validate(&delegate)?;
+    DELEGATES.remove(deps.storage, (&airdrop_addr, &delegate));
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_delegate"),
+        ("airdrop_addr", airdrop_addr.as_str()),
+        ("delegate", delegate.as_str()),
+    ]))
+}
+
+fn query_merkle_root(deps: Deps, airdrop_addr: &Addr, stage: u8) -> StdResult<MerkleRootResponse> {
+    let req = QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: airdrop_addr.to_string(),
+        msg: to_binary(&MerkleRoot { stage })?,
+    });
+    deps.querier.query(&req)
+}
+
+fn query_airdrop_owner(deps: Deps, airdrop_addr: &Addr) -> StdResult<Option<Addr>> {
+    let req = QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: airdrop_addr.to_string(),
+        msg: to_binary(&AirdropConfig {})?,
+    });
+    let res: AirdropConfigResponse = deps.querier.query(&req)?;
+    res.owner.map(|o| deps.api.addr_validate(&o)).transpose()
+}
+
+pub fn execute_add_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_addr: String,
+    escrow_amount: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token_addr = deps.api.addr_validate(&token_addr)?;
+    CW20_ASSETS.save(deps.storage, &token_addr, &escrow_amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "add_cw20"),
+        ("token_addr", token_addr.as_str()),
+        ("escrow_amount", &escrow_amount.to_string()),
+    ]))
+}
+
+pub fn execute_remove_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_addr: String,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token_addr = deps.api.addr_validate(&token_addr)?;
+    CW20_ASSETS.remove(deps.storage, &token_addr);
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "remove_cw20"),
+        ("token_addr", token_addr.as_str()),
+    ]))
+}
+
 pub fn execute_lock_funds(
     deps: DepsMut,
     info: MessageInfo,
     env: Env,
     airdrop_contract_addr: String,
 ) -> Result<Response, ContractError> {
-    let cfg = CONFIG.load(deps.storage)?;
+    let mut funds = NativeBalance(info.funds);
+    funds.normalize();
+
+    // the escrow is paid in the first accepted denom sent with enough funds
+    let mut escrow_coin = None;
+    for coin in &funds.0 {
+        if let Some(amount) = NATIVE_ASSETS.may_load(deps.storage, &coin.denom)? {
+            if coin.amount >= amount {
+                escrow_coin = Some(Coin {
+                    denom: coin.denom.clone(),
+                    amount,
+                });
+                break;
+            }
+        }
+    }
+    let escrow_coin = escrow_coin.ok_or(ContractError::InsufficientAmount {})?;
+    // anything sent on top of the escrow amount is refunded
+    let surplus = (funds - escrow_coin.clone())?;
+
+    let mut res = lock_escrow(
+        deps,
+        env,
+        info.sender.clone(),
+        airdrop_contract_addr,
+        AssetType::Native,
+        escrow_coin,
+    )?;
+    if !surplus.is_empty() {
+        let refund = surplus.into_vec();
+        res = res
+            .add_attribute("refund", join_coins(&refund))
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: refund,
+            });
+    }
+
+    Ok(res)
+}
 
-    let funds = NativeBalance(info.funds);
-    if !funds.has(&Coin {
-        denom: cfg.allowed_native,
-        amount: cfg.escrow_amount,
-    }) {
+pub fn execute_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // the sender of a receive hook is the token contract
+    let escrow_amount = CW20_ASSETS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::Cw20NotAccepted {})?;
+    if wrapper.amount < escrow_amount {
         return Err(ContractError::InsufficientAmount {});
     }
+    let source = deps.api.addr_validate(&wrapper.sender)?;
+
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::LockFunds { airdrop_addr } => {
+            let escrow_coin = Coin {
+                denom: info.sender.to_string(),
+                amount: escrow_amount,
+            };
+            let mut res = lock_escrow(
+                deps,
+                env,
+                source.clone(),
+                airdrop_addr,
+                AssetType::Cw20,
+                escrow_coin,
+            )?;
+
+            let surplus = wrapper.amount - escrow_amount;
+            if !surplus.is_zero() {
+                let refund = Coin {
+                    denom: info.sender.to_string(),
+                    amount: surplus,
+                };
+                res = res
+                    .add_attribute("refund", refund.to_string())
+                    .add_message(payout(&AssetType::Cw20, refund, &source)?);
+            }
+            Ok(res)
+        }
+    }
+}
+
+fn lock_escrow(
+    deps: DepsMut,
+    env: Env,
+    source: Addr,
+    airdrop_contract_addr: String,
+    asset_type: AssetType,
+    escrow_coin: Coin,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
 
     let airdrop_addr = deps.api.addr_validate(airdrop_contract_addr.as_str())?;
+
+    // a contract of unknown code could report any stage
+    let req = QueryRequest::Wasm(WasmQuery::ContractInfo {
+        contract_addr: airdrop_contract_addr.clone(),
+    });
+    let info: ContractInfoResponse = deps.querier.query(&req)?;
+    if !AIRDROP_CODE_IDS.has(deps.storage, info.code_id) {
+        return Err(ContractError::CodeIdNotApproved {
+            code_id: info.code_id,
+        });
+    }
+
     let query_msg = LatestStage {};
     let req = QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: airdrop_contract_addr.clone(),
@@ -150,6 +499,16 @@ pub fn execute_lock_funds(
     let res: LatestStageResponse = deps.querier.query(&req)?;
     let stage = res.latest_stage;
 
+    // only the airdrop owner and its delegates can lock escrows
+    if query_airdrop_owner(deps.as_ref(), &airdrop_addr)?.as_ref() != Some(&source)
+        && !DELEGATES.has(deps.storage, (&airdrop_addr, &source))
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    query_merkle_root(deps.as_ref(), &airdrop_addr, stage)
+        .map_err(|_| ContractError::NoMerkleRoot { stage })?;
+
     if ESCROWS
         .may_load(deps.storage, (&airdrop_addr, stage))?
         .is_some()
@@ -160,25 +519,64 @@ pub fn execute_lock_funds(
     }
 
     let escrow = Escrow {
-        source: info.sender.clone(),
-        expiration: Expiration::AtHeight(cfg.release_height_delta.u64() + env.block.height),
-        escrow_amount: cfg.escrow_amount,
+        source: source.clone(),
+        expiration: escrow_expiration(&cfg, &env),
+        escrow_coin: escrow_coin.clone(),
+        asset_type,
+        splits: cfg.splits,
+        release_policy: cfg.release_policy,
         latest_stage: res.latest_stage,
         released: false,
     };
 
     ESCROWS.save(deps.storage, (&airdrop_addr, stage), &escrow)?;
+    UNRELEASED_ESCROWS.save(deps.storage, (&airdrop_addr, stage), &Empty {})?;
 
     let res = Response::new().add_attributes(vec![
         ("action", "lock_funds"),
-        ("amount", &cfg.escrow_amount.to_string()),
-        ("sender", &info.sender.to_string()),
+        ("amount", &escrow_coin.to_string()),
+        ("sender", source.as_str()),
         ("airdrop_addr", &airdrop_contract_addr),
     ]);
 
     Ok(res)
 }
 
+fn escrow_expiration(cfg: &Config, env: &Env) -> Expiration {
+    match cfg.release_time_delta {
+        Some(delta) => Expiration::AtTime(env.block.time.plus_seconds(delta.u64())),
+        None => Expiration::AtHeight(cfg.release_height_delta.u64() + env.block.height),
+    }
+}
+
+/// message paying out an escrowed coin
+fn payout(asset_type: &AssetType, coin: Coin, recipient: &Addr) -> StdResult<CosmosMsg> {
+    match asset_type {
+        AssetType::Native => Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin],
+        }
+        .into()),
+        AssetType::Cw20 => Ok(WasmMsg::Execute {
+            contract_addr: coin.denom,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: coin.amount,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+fn join_coins(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 pub fn execute_release_funds(
     deps: DepsMut,
     _info: MessageInfo,
@@ -194,65 +592,308 @@ pub fn execute_release_funds(
         return Err(ContractError::EscrowAlreadyReleased {});
     }
 
-    // if expired dao can withdraw
-    if escrow.expiration.is_expired(&env.block) {
-        // update escrow
+    // if expired dao can withdraw, if not expired, and the stage made progress,
+    // this means we can release funds to the source
+    let expired = escrow.expiration.is_expired(&env.block);
+    if !expired
+        && !release_policy_met(
+            deps.as_ref(),
+            &env,
+            &escrow.release_policy,
+            &airdrop_addr,
+            escrow.latest_stage,
+        )?
+    {
+        return Err(ContractError::CannotReleaseFunds {});
+    }
+
+    // update escrow
+    escrow.released = true;
+    ESCROWS.save(deps.storage, (&airdrop_addr, stage), &escrow)?;
+    UNRELEASED_ESCROWS.remove(deps.storage, (&airdrop_addr, stage));
+
+    let release_addr = if expired {
+        &cfg.release_addr
+    } else {
+        &escrow.source
+    };
+    let mut res = Response::new().add_attributes(vec![
+        ("action", "release_funds"),
+        ("escrow_amount", &escrow.escrow_coin.to_string()),
+        ("release_addr", release_addr.as_str()),
+        ("airdrop_addr", airdrop_addr.as_str()),
+    ]);
+    // every recipient of the split is reported with its amount
+    for (recipient, coin) in escrow_payouts(&escrow, &cfg.release_addr, expired) {
+        res = res
+            .add_attribute("payout", format!("{}:{}", recipient, coin))
+            .add_message(payout(&escrow.asset_type, coin, &recipient)?);
+    }
+    Ok(res)
+}
+
+pub fn execute_release_expired(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<(String, u8)>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|(airdrop_addr, stage)| deps.api.addr_validate(&airdrop_addr).map(|a| (a, stage)))
+        .transpose()?;
+
+    // the limit bounds the scanned escrows, expired or not
+    let scanned = UNRELEASED_ESCROWS
+        .keys(
+            deps.storage,
+            start
+                .as_ref()
+                .map(|(airdrop_addr, stage)| Bound::exclusive((airdrop_addr, *stage))),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let cursor = scanned
+        .last()
+        .map(|(airdrop_addr, stage)| format!("{}:{}", airdrop_addr, stage));
+
+    let mut expired = vec![];
+    for (airdrop_addr, stage) in scanned {
+        let escrow = ESCROWS.load(deps.storage, (&airdrop_addr, stage))?;
+        if escrow.expiration.is_expired(&env.block) {
+            expired.push((airdrop_addr, stage, escrow));
+        }
+    }
+
+    // payments keyed by recipient and denom, or token address for cw20
+    let mut native_payments: BTreeMap<(Addr, String), Uint128> = BTreeMap::new();
+    let mut cw20_payments: BTreeMap<(Addr, String), Uint128> = BTreeMap::new();
+    let mut events = vec![];
+    for (airdrop_addr, stage, mut escrow) in expired {
         escrow.released = true;
         ESCROWS.save(deps.storage, (&airdrop_addr, stage), &escrow)?;
+        UNRELEASED_ESCROWS.remove(deps.storage, (&airdrop_addr, stage));
+
+        // each release is reported on its own, payments are aggregated
+        let mut event = Event::new("release_escrow")
+            .add_attribute("airdrop_addr", &airdrop_addr)
+            .add_attribute("stage", stage.to_string())
+            .add_attribute("escrow_amount", escrow.escrow_coin.to_string());
+        let payments = match escrow.asset_type {
+            AssetType::Native => &mut native_payments,
+            AssetType::Cw20 => &mut cw20_payments,
+        };
+        for (recipient, coin) in escrow_payouts(&escrow, &cfg.release_addr, true) {
+            event = event.add_attribute("payout", format!("{}:{}", recipient, coin));
+            *payments.entry((recipient, coin.denom)).or_default() += coin.amount;
+        }
+        events.push(event);
+    }
 
-        let send_fund_msg = BankMsg::Send {
-            to_address: cfg.release_addr.to_string(),
-            amount: vec![Coin {
-                denom: cfg.allowed_native,
-                amount: escrow.escrow_amount,
-            }],
+    let messages = native_payments
+        .into_iter()
+        .map(|payment| (AssetType::Native, payment))
+        .chain(
+            cw20_payments
+                .into_iter()
+                .map(|payment| (AssetType::Cw20, payment)),
+        )
+        .map(|(asset_type, ((recipient, denom), amount))| {
+            payout(&asset_type, Coin { denom, amount }, &recipient)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "release_expired"),
+            ("released", &events.len().to_string()),
+            ("release_addr", cfg.release_addr.as_str()),
+        ])
+        .add_attributes(cursor.map(|cursor| ("last_scanned", cursor)))
+        .add_events(events))
+}
+
+/// coins of a released escrow paid to its source and to the release address, without empty ones
+fn escrow_payouts(escrow: &Escrow, release_addr: &Addr, expired: bool) -> Vec<(Addr, Coin)> {
+    let (source_amount, release_amount) = split_escrow(escrow, expired);
+    vec![
+        (escrow.source.clone(), source_amount),
+        (release_addr.clone(), release_amount),
+    ]
+    .into_iter()
+    .filter(|(_, amount)| !amount.is_zero())
+    .map(|(recipient, amount)| {
+        let coin = Coin {
+            denom: escrow.escrow_coin.denom.clone(),
+            amount,
         };
+        (recipient, coin)
+    })
+    .collect()
+}
 
-        let res = Response::new()
-            .add_message(CosmosMsg::from(send_fund_msg))
-            .add_attributes(vec![
-                ("action", "release_funds"),
-                ("escrow_amount", &cfg.escrow_amount.to_string()),
-                ("release_addr", &cfg.release_addr.to_string()),
-                ("airdrop_addr", &airdrop_addr.to_string()),
-            ]);
-        return Ok(res);
+/// amounts of an escrow paid to its source and to the release address
+fn split_escrow(escrow: &Escrow, expired: bool) -> (Uint128, Uint128) {
+    let amount = escrow.escrow_coin.amount;
+    if expired {
+        let source_amount =
+            amount.multiply_ratio(escrow.splits.expired_source_bps, BPS_DENOMINATOR);
+        (source_amount, amount - source_amount)
+    } else {
+        let release_amount = amount.multiply_ratio(escrow.splits.success_fee_bps, BPS_DENOMINATOR);
+        (amount - release_amount, release_amount)
     }
+}
 
-    // query latest stage
-    let query_msg = LatestStage {};
-    let req = QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: airdrop_addr.to_string(),
-        msg: to_binary(&query_msg)?,
-    });
-    let stage_res: LatestStageResponse = deps.querier.query(&req)?;
-
-    // if not expired, and stage has passed, this means we can release funds
-    if stage_res.latest_stage > escrow.latest_stage {
-        let send_fund_msg = BankMsg::Send {
-            to_address: escrow.source.to_string(),
-            amount: vec![Coin {
-                denom: cfg.allowed_native,
-                amount: escrow.escrow_amount,
-            }],
-        };
+/// basis points of a whole
+const BPS_DENOMINATOR: u16 = 10_000;
+
+/// bps basis points of total_amount, rounded up so a non-zero ratio always needs a claim
+fn min_claimed(total_amount: Uint128, bps: u16) -> Uint128 {
+    let min_claimed = total_amount.multiply_ratio(bps, BPS_DENOMINATOR);
+    // (total_amount * bps) % denominator, without overflowing
+    let remainder =
+        (total_amount.u128() % BPS_DENOMINATOR as u128) * bps as u128 % BPS_DENOMINATOR as u128;
+    if remainder == 0 {
+        min_claimed
+    } else {
+        min_claimed + Uint128::new(1)
+    }
+}
 
-        // update escrow
-        escrow.released = true;
-        ESCROWS.save(deps.storage, (&airdrop_addr, stage), &escrow)?;
+fn release_policy_met(
+    deps: Deps,
+    env: &Env,
+    policy: &ReleasePolicy,
+    airdrop_addr: &Addr,
+    stage: u8,
+) -> StdResult<bool> {
+    match policy {
+        ReleasePolicy::StageIncreased {} => {
+            let req = QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: airdrop_addr.to_string(),
+                msg: to_binary(&LatestStage {})?,
+            });
+            let res: LatestStageResponse = deps.querier.query(&req)?;
+            Ok(res.latest_stage > stage)
+        }
+        ReleasePolicy::ClaimedRatio { min_claimed_bps } => {
+            let root = query_merkle_root(deps, airdrop_addr, stage)?;
+            // an empty stage can not make progress
+            if root.total_amount.is_zero() {
+                return Ok(false);
+            }
+            let req = QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: airdrop_addr.to_string(),
+                msg: to_binary(&TotalClaimed { stage })?,
+            });
+            let res: TotalClaimedResponse = deps.querier.query(&req)?;
+            Ok(res.total_claimed >= min_claimed(root.total_amount, *min_claimed_bps))
+        }
+        ReleasePolicy::MinDurationSinceStart { duration } => {
+            let root = query_merkle_root(deps, airdrop_addr, stage)?;
+            // a start at height can not be compared to a time duration, and the other way around
+            match root.start.map(|start| start + *duration) {
+                Some(Ok(end)) => Ok(end.is_triggered(&env.block)),
+                _ => Ok(false),
+            }
+        }
+        ReleasePolicy::StageExpired {} => {
+            let root = query_merkle_root(deps, airdrop_addr, stage)?;
+            Ok(root.expiration.is_expired(&env.block))
+        }
+    }
+}
+
+/// Config as stored before the accepted native denoms moved into their own map
+#[derive(Serialize, Deserialize)]
+struct LegacyConfig {
+    #[serde(default)]
+    escrow_amount: Option<Uint128>,
+    #[serde(default)]
+    allowed_native: Option<String>,
+}
+
+/// Escrow as stored before the escrowed coin was kept on the escrow
+#[derive(Serialize, Deserialize)]
+struct LegacyEscrow {
+    source: Addr,
+    expiration: Expiration,
+    #[serde(default)]
+    escrow_amount: Option<Uint128>,
+    latest_stage: u8,
+    released: bool,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    if version.contract != CONTRACT_NAME || version.version != PREVIOUS_VERSION {
+        return Err(ContractError::CannotMigrate {
+            contract: version.contract,
+            version: version.version,
+        });
+    }
+
+    let legacy_cfg: LegacyConfig = Item::new("config").load(deps.storage)?;
+    if let (Some(denom), Some(amount)) = (&legacy_cfg.allowed_native, legacy_cfg.escrow_amount) {
+        NATIVE_ASSETS.save(deps.storage, denom, &amount)?;
+    }
+    // saving drops the native denom from the stored config
+    let cfg = CONFIG.load(deps.storage)?;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    let legacy_escrows: Map<(&Addr, u8), LegacyEscrow> = Map::new("escrow");
+    let escrows = legacy_escrows
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
 
-        let res = Response::new()
-            .add_message(CosmosMsg::from(send_fund_msg))
-            .add_attributes(vec![
-                ("action", "release_funds"),
-                ("escrow_amount", &cfg.escrow_amount.to_string()),
-                ("release_addr", &escrow.source.to_string()),
-                ("airdrop_addr", &airdrop_addr.to_string()),
-            ]);
-        return Ok(res);
+    // escrows without an amount are already migrated
+    let mut migrated = 0u32;
+    for ((airdrop_addr, stage), legacy) in escrows {
+        if let (Some(amount), Some(denom)) = (legacy.escrow_amount, &legacy_cfg.allowed_native) {
+            let escrow = Escrow {
+                source: legacy.source,
+                expiration: legacy.expiration,
+                // legacy escrows were locked and released in the allowed native denom
+                escrow_coin: Coin {
+                    denom: denom.clone(),
+                    amount,
+                },
+                asset_type: AssetType::Native,
+                splits: ReleaseSplits::default(),
+                release_policy: cfg.release_policy.clone(),
+                latest_stage: legacy.latest_stage,
+                released: legacy.released,
+            };
+            ESCROWS.save(deps.storage, (&airdrop_addr, stage), &escrow)?;
+            if !escrow.released {
+                UNRELEASED_ESCROWS.save(deps.storage, (&airdrop_addr, stage), &Empty {})?;
+            }
+            migrated += 1;
+        }
+    }
+    // previous versions accepted escrows for any airdrop contract
+    for code_id in &msg.airdrop_code_ids {
+        AIRDROP_CODE_IDS.save(deps.storage, *code_id, &Empty {})?;
     }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    return Err(ContractError::CannotReleaseFunds {});
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("migrated_escrows", migrated.to_string())
+        .add_attribute(
+            "airdrop_code_ids",
+            msg.airdrop_code_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -269,17 +910,47 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::ListExpiredEscrows { start_after, limit } => {
             to_binary(&query_list_expired_escrows(deps, env, start_after, limit)?)
         },
+        QueryMsg::ListDelegates {
+            airdrop_addr,
+            start_after,
+            limit,
+        } => to_binary(&query_list_delegates(
+            deps,
+            airdrop_addr,
+            start_after,
+            limit,
+        )?),
     }
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let cfg = CONFIG.load(deps.storage)?;
-    Ok(ConfigResponse {
+    let native_assets = NATIVE_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    let cw20_assets = CW20_ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(address, amount)| Cw20Coin {
+                address: address.to_string(),
+                amount,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let airdrop_code_ids = AIRDROP_CODE_IDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ConfigResponse {
         admin: cfg.admin,
-        escrow_amount: cfg.escrow_amount,
         release_height_delta: cfg.release_height_delta,
+        release_time_delta: cfg.release_time_delta,
+        release_policy: cfg.release_policy,
+        splits: cfg.splits,
         release_addr: cfg.release_addr,
-        allowed_native: cfg.allowed_native,
+        native_assets,
+        cw20_assets,
+        airdrop_code_ids,
     })
 }
 
@@ -287,13 +958,20 @@ fn query_escrow(deps: Deps, airdrop_addr: String, stage: u8) -> StdResult<Escrow
     let addr = deps.api.addr_validate(&airdrop_addr)?;
     let escrow = ESCROWS.load(deps.storage, (&addr, stage))?;
 
-    Ok(EscrowResponse {
+    Ok(escrow_response(escrow))
+}
+
+fn escrow_response(escrow: Escrow) -> EscrowResponse {
+    EscrowResponse {
         source: escrow.source.to_string(),
         expiration: escrow.expiration,
-        escrow_amount: escrow.escrow_amount,
+        escrow_coin: escrow.escrow_coin,
+        asset_type: escrow.asset_type,
+        splits: escrow.splits,
+        release_policy: escrow.release_policy,
         latest_stage: escrow.latest_stage,
         released: escrow.released,
-    })
+    }
 }
 
 // Settings for pagination
@@ -315,13 +993,7 @@ fn query_list_escrows(
         .collect::<StdResult<Vec<_>>>()?;
     let escrows = escrows
         .into_iter()
-        .map(|(_, e)| EscrowResponse {
-            source: e.source.to_string(),
-            expiration: e.expiration,
-            escrow_amount: e.escrow_amount,
-            latest_stage: e.latest_stage,
-            released: e.released,
-        })
+        .map(|(_, e)| escrow_response(e))
         .collect();
 
     Ok(ListEscrowsResponse { escrows })
@@ -344,24 +1016,45 @@ fn query_list_expired_escrows(
     let escrows = escrows
         .into_iter()
         .filter(|(_, e)| e.expiration.is_expired(&env.block) && e.released == false)
-        .map(|(_, e)| EscrowResponse {
-            source: e.source.to_string(),
-            expiration: e.expiration,
-            escrow_amount: e.escrow_amount,
-            latest_stage: e.latest_stage,
-            released: e.released,
-        })
+        .map(|(_, e)| escrow_response(e))
         .collect();
 
     Ok(ListEscrowsResponse { escrows })
 }
 
+fn query_list_delegates(
+    deps: Deps,
+    airdrop_addr: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListDelegatesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let airdrop_addr = deps.api.addr_validate(&airdrop_addr)?;
+    let start = start_after
+        .map(|s| deps.api.addr_validate(&s))
+        .transpose()?;
+
+    let delegates = DELEGATES
+        .prefix(&airdrop_addr)
+        .keys(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|delegate| delegate.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListDelegatesResponse { delegates })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use cosmwasm_std::{Addr, Empty, Uint64, WasmMsg};
-    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+    use cosmwasm_std::{Addr, BlockInfo, Empty, Uint64, WasmMsg};
+    use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
 
     pub fn contract_jt_airdrop_controller() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -397,6 +1090,23 @@ mod tests {
                 .init_balance(
                     storage,
                     &Addr::unchecked(USER),
+                    vec![
+                        Coin {
+                            denom: NATIVE_DENOM.to_string(),
+                            amount: ESCROW_AMOUNT.into(),
+                        },
+                        Coin {
+                            denom: OTHER_DENOM.to_string(),
+                            amount: ESCROW_AMOUNT.into(),
+                        },
+                    ],
+                )
+                .unwrap();
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked(RANDOM),
                     vec![Coin {
                         denom: NATIVE_DENOM.to_string(),
                         amount: ESCROW_AMOUNT.into(),
@@ -411,6 +1121,7 @@ mod tests {
     const RANDOM: &str = "juno1hfx3mlyy30450u8fe5enyywtl3e2wnkhuy44qg";
     const RELEASE_ADDR: &str = "juno19ayrkzcfpgw8ht7cugmcmm4ca96zwp5auect33";
     const NATIVE_DENOM: &str = "ujunox";
+    const OTHER_DENOM: &str = "uother";
     const ESCROW_AMOUNT: u128 = 100;
     const DEFAULT_RELEASE: u64 = 10;
     const MERKLE_ROOT: &str = "b45c1ea28b26adb13e412933c9e055b01fdf7585304b00cd8f1cb220aa6c5e88";
@@ -444,7 +1155,7 @@ mod tests {
             .unwrap();
 
         let msg = cw20_merkle_airdrop::msg::InstantiateMsg {
-            owner: Some(ADMIN.to_string()),
+            owner: Some(USER.to_string()),
             cw20_token_address: cw20_base_addr.to_string(),
         };
         let cw20_airdrop_addr = app
@@ -465,18 +1176,25 @@ mod tests {
             total_amount: None,
         };
 
-        let _cosmos_msg: CosmosMsg<Empty> = CosmosMsg::from(WasmMsg::Execute {
+        let cosmos_msg: CosmosMsg<Empty> = CosmosMsg::from(WasmMsg::Execute {
             contract_addr: cw20_airdrop_addr.clone().to_string(),
             msg: to_binary(&register_msg).unwrap(),
             funds: vec![],
         });
+        app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
         let msg = InstantiateMsg {
             admin: Some(ADMIN.to_string()),
             release_addr: Some(RELEASE_ADDR.to_string()),
-            escrow_amount: Uint128::new(ESCROW_AMOUNT),
-            allowed_native: NATIVE_DENOM.to_string(),
+            native_assets: vec![Coin {
+                denom: NATIVE_DENOM.to_string(),
+                amount: Uint128::new(ESCROW_AMOUNT),
+            }],
+            airdrop_code_ids: vec![cw20_merkle_id],
             release_height_delta: Uint64::new(DEFAULT_RELEASE),
+            release_time_delta: None,
+            release_policy: Some(ReleasePolicy::StageIncreased {}),
+            splits: None,
         };
         let jt_controller_addr = app
             .instantiate_contract(
@@ -539,6 +1257,214 @@ mod tests {
             });
             app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
         }
+
+        #[test]
+        fn owner_or_delegate() {
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            let lock_msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+            };
+            let funds = vec![Coin {
+                denom: NATIVE_DENOM.to_string(),
+                amount: Uint128::new(ESCROW_AMOUNT),
+            }];
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(RANDOM),
+                    Addr::unchecked(&jt_controller_addr),
+                    &lock_msg,
+                    &funds,
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::Unauthorized {}
+            ));
+
+            // only the airdrop owner can add delegates
+            let msg = ExecuteMsg::AddDelegate {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+                delegate: RANDOM.to_string(),
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(RANDOM),
+                    Addr::unchecked(&jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::Unauthorized {}
+            ));
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+
+            let res: ListDelegatesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &jt_controller_addr,
+                    &QueryMsg::ListDelegates {
+                        airdrop_addr: cw20_airdrop_addr.clone(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.delegates, vec![RANDOM.to_string()]);
+
+            app.execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &lock_msg,
+                &funds,
+            )
+            .unwrap();
+            let res: EscrowResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    jt_controller_addr,
+                    &QueryMsg::Escrow {
+                        airdrop_addr: cw20_airdrop_addr,
+                        stage: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.source, RANDOM);
+        }
+
+        #[test]
+        fn no_merkle_root() {
+            let (mut app, cw20_base_addr, _cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            let cw20_merkle_id = app.store_code(contract_cw20_merkle_airdrop());
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &ExecuteMsg::AddAirdropCodeId {
+                    code_id: cw20_merkle_id,
+                },
+                &[],
+            )
+            .unwrap();
+            let msg = cw20_merkle_airdrop::msg::InstantiateMsg {
+                owner: Some(USER.to_string()),
+                cw20_token_address: cw20_base_addr,
+            };
+            let cw20_airdrop_addr = app
+                .instantiate_contract(
+                    cw20_merkle_id,
+                    Addr::unchecked(ADMIN),
+                    &msg,
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap();
+
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr.to_string(),
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    Addr::unchecked(jt_controller_addr),
+                    &msg,
+                    &[Coin {
+                        denom: NATIVE_DENOM.to_string(),
+                        amount: Uint128::new(ESCROW_AMOUNT),
+                    }],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::NoMerkleRoot { stage: 0 }
+            ));
+        }
+
+        #[test]
+        fn unknown_code_id() {
+            let (mut app, cw20_base_addr, _cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            // a cw20 contract can not pass for an airdrop
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_base_addr,
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    Addr::unchecked(jt_controller_addr),
+                    &msg,
+                    &[Coin {
+                        denom: NATIVE_DENOM.to_string(),
+                        amount: Uint128::new(ESCROW_AMOUNT),
+                    }],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::CodeIdNotApproved { .. }
+            ));
+        }
+
+        #[test]
+        fn refunds_surplus() {
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr,
+            };
+            let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: jt_controller_addr.clone(),
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![
+                    Coin {
+                        denom: NATIVE_DENOM.to_string(),
+                        amount: Uint128::new(ESCROW_AMOUNT),
+                    },
+                    Coin {
+                        denom: OTHER_DENOM.to_string(),
+                        amount: Uint128::new(40),
+                    },
+                ],
+            });
+            let res = app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            let refund = res
+                .events
+                .into_iter()
+                .find(|e| e.ty == "wasm")
+                .unwrap()
+                .attributes
+                .into_iter()
+                .find(|e| e.key == "refund")
+                .unwrap();
+            assert_eq!(refund.value, format!("40{}", OTHER_DENOM));
+
+            // only the escrow amount stays locked
+            let balance = app.wrap().query_balance(USER, OTHER_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::new(ESCROW_AMOUNT));
+            let balance = app
+                .wrap()
+                .query_balance(&jt_controller_addr, OTHER_DENOM)
+                .unwrap();
+            assert_eq!(balance.amount, Uint128::zero());
+            let balance = app
+                .wrap()
+                .query_balance(jt_controller_addr, NATIVE_DENOM)
+                .unwrap();
+            assert_eq!(balance.amount, Uint128::new(ESCROW_AMOUNT));
+        }
     }
 
     mod release_funds {
@@ -579,7 +1505,7 @@ mod tests {
 
             let msg = ExecuteMsg::ReleaseLockedFunds {
                 airdrop_addr: cw20_airdrop_addr.clone(),
-                stage: 0,
+                stage: 1,
             };
             let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
                 contract_addr: jt_controller_addr.clone(),
@@ -648,7 +1574,7 @@ mod tests {
                 msg: to_binary(&register_msg).unwrap(),
                 funds: vec![],
             });
-            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
 
             app.set_block(BlockInfo {
                 height: 5,
@@ -658,7 +1584,7 @@ mod tests {
 
             let msg = ExecuteMsg::ReleaseLockedFunds {
                 airdrop_addr: cw20_airdrop_addr.clone(),
-                stage: 0,
+                stage: 1,
             };
             let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
                 contract_addr: jt_controller_addr.clone(),
@@ -685,93 +1611,1135 @@ mod tests {
                 }
             )
         }
-    }
-
-    #[test]
-    fn update_config() {
-        let (mut app, _cw20_base_addr, _cw20_airdrop_addr, jt_controller_addr) =
-            proper_instantiate();
-
-        let msg = ExecuteMsg::UpdateConfig {
-            admin: Some("new_admin".to_string()),
-            release_addr: Some("new_release".to_string()),
-            escrow_amount: Some(Uint128::new(6)),
-            release_height_delta: Some(Uint64::new(69)),
-            allowed_native: Some("unew".to_string()),
-        };
-
-        let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
-            contract_addr: jt_controller_addr.clone(),
-            msg: to_binary(&msg).unwrap(),
-            funds: vec![],
-        });
-        // unauthorized
-        let err = app
-            .execute(Addr::unchecked(RANDOM), cosmos_msg.clone())
-            .unwrap_err();
-
-        assert!(matches!(
-            err.downcast().unwrap(),
-            ContractError::Unauthorized {}
-        ));
-
-        app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
-
-        let res: ConfigResponse = app
-            .wrap()
-            .query_wasm_smart(jt_controller_addr, &QueryMsg::Config {})
-            .unwrap();
-
-        assert_eq!(res.admin, "new_admin");
-        assert_eq!(res.release_addr, "new_release");
-        assert_eq!(res.escrow_amount, Uint128::new(6));
-        assert_eq!(res.release_height_delta, Uint64::new(69));
-        assert_eq!(res.allowed_native, "unew");
-    }
-
-    mod queries {
-        use super::*;
-        use cosmwasm_std::BlockInfo;
 
+        // a new empty stage does not release the escrow under a stricter policy
         #[test]
-        fn query_expired_escrows() {
-            let (mut app, _cw20_base_addr, _cw20_airdrop_addr, jt_controller_addr) =
+        fn release_policy() {
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
                 proper_instantiate();
-    
+
             app.set_block(BlockInfo {
                 height: 1,
                 time: Default::default(),
                 chain_id: "".to_string(),
             });
-    
-            // cannot send without tokens
+
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: Some(Uint64::new(1000)),
+                release_time_delta: None,
+                release_policy: Some(ReleasePolicy::ClaimedRatio {
+                    min_claimed_bps: 10_001,
+                }),
+                splits: None,
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    Addr::unchecked(&jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::InvalidReleasePolicy {}
+            ));
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: Some(Uint64::new(1000)),
+                release_time_delta: None,
+                release_policy: Some(ReleasePolicy::ClaimedRatio { min_claimed_bps: 0 }),
+                splits: None,
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    Addr::unchecked(&jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::InvalidReleasePolicy {}
+            ));
+
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: Some(Uint64::new(1000)),
+                release_time_delta: None,
+                release_policy: Some(ReleasePolicy::StageExpired {}),
+                splits: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+
+            // stage 2 expires at height 20
+            let register_msg = cw20_merkle_airdrop::msg::ExecuteMsg::RegisterMerkleRoot {
+                merkle_root: MERKLE_ROOT.to_string(),
+                expiration: Some(Expiration::AtHeight(20)),
+                start: None,
+                total_amount: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&cw20_airdrop_addr),
+                &register_msg,
+                &[],
+            )
+            .unwrap();
+
             let msg = ExecuteMsg::LockFunds {
-                airdrop_addr: _cw20_airdrop_addr.clone(),
+                airdrop_addr: cw20_airdrop_addr.clone(),
             };
-            let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
-                contract_addr: jt_controller_addr.clone(),
-                msg: to_binary(&msg).unwrap(),
-                funds: vec![Coin {
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[Coin {
                     denom: NATIVE_DENOM.to_string(),
                     amount: Uint128::new(ESCROW_AMOUNT),
                 }],
-            });
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
-    
-            let msg = QueryMsg::ListExpiredEscrows { start_after: None, limit: None };
-            let res: ListEscrowsResponse = app
-            .wrap()
-            .query_wasm_smart(&jt_controller_addr, &msg)
+            )
             .unwrap();
-            assert_eq!(res.escrows.len(), 0);
+
+            // registering stage 3 is not enough
+            let register_msg = cw20_merkle_airdrop::msg::ExecuteMsg::RegisterMerkleRoot {
+                merkle_root: MERKLE_ROOT.to_string(),
+                expiration: None,
+                start: None,
+                total_amount: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&cw20_airdrop_addr),
+                &register_msg,
+                &[],
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::ReleaseLockedFunds {
+                airdrop_addr: cw20_airdrop_addr,
+                stage: 2,
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(RANDOM),
+                    Addr::unchecked(&jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::CannotReleaseFunds {}
+            ));
+
+            // the escrow keeps the policy it was locked with
+            let update_msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: None,
+                release_time_delta: None,
+                release_policy: Some(ReleasePolicy::StageIncreased {}),
+                splits: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &update_msg,
+                &[],
+            )
+            .unwrap();
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(RANDOM),
+                    Addr::unchecked(&jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::CannotReleaseFunds {}
+            ));
 
             app.set_block(BlockInfo {
-                height: 150,
+                height: 20,
                 time: Default::default(),
                 chain_id: "".to_string(),
             });
+            app.execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(USER, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::new(ESCROW_AMOUNT));
+        }
 
-            let msg = QueryMsg::ListExpiredEscrows { start_after: None, limit: None };
+        // a time duration is never met by a stage starting at a height
+        #[test]
+        fn duration_kind_mismatch() {
+            use cosmwasm_std::Timestamp;
+            use cw_utils::{Duration, Scheduled};
+
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            app.set_block(BlockInfo {
+                height: 1,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: Some(Uint64::new(1000)),
+                release_time_delta: None,
+                release_policy: Some(ReleasePolicy::MinDurationSinceStart {
+                    duration: Duration::Time(100),
+                }),
+                splits: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+
+            // stage 2 starts at height 1
+            let register_msg = cw20_merkle_airdrop::msg::ExecuteMsg::RegisterMerkleRoot {
+                merkle_root: MERKLE_ROOT.to_string(),
+                expiration: None,
+                start: Some(Scheduled::AtHeight(1)),
+                total_amount: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&cw20_airdrop_addr),
+                &register_msg,
+                &[],
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(ESCROW_AMOUNT),
+                }],
+            )
+            .unwrap();
+
+            app.set_block(BlockInfo {
+                height: 200,
+                time: Timestamp::from_seconds(1000),
+                chain_id: "".to_string(),
+            });
+            let msg = ExecuteMsg::ReleaseLockedFunds {
+                airdrop_addr: cw20_airdrop_addr,
+                stage: 2,
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(RANDOM),
+                    Addr::unchecked(jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::CannotReleaseFunds {}
+            ));
+        }
+
+        #[test]
+        fn split_release() {
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            app.set_block(BlockInfo {
+                height: 1,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: None,
+                release_time_delta: None,
+                release_policy: None,
+                splits: Some(ReleaseSplits {
+                    expired_source_bps: 2_000,
+                    success_fee_bps: 10_001,
+                }),
+            };
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    Addr::unchecked(&jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::InvalidSplits {}
+            ));
+
+            let splits = ReleaseSplits {
+                expired_source_bps: 2_000,
+                success_fee_bps: 1_000,
+            };
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: None,
+                release_time_delta: None,
+                release_policy: None,
+                splits: Some(splits.clone()),
+            };
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(ESCROW_AMOUNT),
+                }],
+            )
+            .unwrap();
+            let res: EscrowResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &jt_controller_addr,
+                    &QueryMsg::Escrow {
+                        airdrop_addr: cw20_airdrop_addr.clone(),
+                        stage: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.splits, splits);
+
+            app.set_block(BlockInfo {
+                height: 150,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            // an expired escrow returns 20% to the source
+            let msg = ExecuteMsg::ReleaseLockedFunds {
+                airdrop_addr: cw20_airdrop_addr,
+                stage: 1,
+            };
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(RANDOM),
+                    Addr::unchecked(jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap();
+            let payouts: Vec<String> = res
+                .events
+                .into_iter()
+                .find(|e| e.ty == "wasm")
+                .unwrap()
+                .attributes
+                .into_iter()
+                .filter(|a| a.key == "payout")
+                .map(|a| a.value)
+                .collect();
+            assert_eq!(
+                payouts,
+                vec![
+                    format!("{}:20{}", USER, NATIVE_DENOM),
+                    format!("{}:80{}", RELEASE_ADDR, NATIVE_DENOM),
+                ]
+            );
+
+            let balance = app.wrap().query_balance(USER, NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::new(20));
+            let balance = app
+                .wrap()
+                .query_balance(RELEASE_ADDR, NATIVE_DENOM)
+                .unwrap();
+            assert_eq!(balance.amount, Uint128::new(80));
+        }
+
+        // changing the config does not affect locked funds
+        #[test]
+        fn releases_escrowed_coin() {
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            app.set_block(BlockInfo {
+                height: 1,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+            };
+            let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: jt_controller_addr.clone(),
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(ESCROW_AMOUNT),
+                }],
+            });
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+
+            let msg = ExecuteMsg::AddNativeDenom {
+                denom: NATIVE_DENOM.to_string(),
+                escrow_amount: Uint128::new(6),
+            };
+            let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: jt_controller_addr.clone(),
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![],
+            });
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let msg = QueryMsg::Escrow {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+                stage: 1,
+            };
+            let res: EscrowResponse = app
+                .wrap()
+                .query_wasm_smart(&jt_controller_addr, &msg)
+                .unwrap();
+            assert_eq!(
+                res.escrow_coin,
+                Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: ESCROW_AMOUNT.into()
+                }
+            );
+
+            app.set_block(BlockInfo {
+                height: 150,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::ReleaseLockedFunds {
+                airdrop_addr: cw20_airdrop_addr,
+                stage: 1,
+            };
+            let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: jt_controller_addr,
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![],
+            });
+            app.execute(Addr::unchecked(RANDOM), cosmos_msg).unwrap();
+            let balance = app
+                .wrap()
+                .query_balance(RELEASE_ADDR, NATIVE_DENOM)
+                .unwrap();
+            assert_eq!(balance.amount, Uint128::new(ESCROW_AMOUNT));
+        }
+    }
+
+    #[test]
+    fn min_claimed_rounds_up() {
+        assert_eq!(min_claimed(Uint128::new(10), 1), Uint128::new(1));
+        assert_eq!(
+            min_claimed(Uint128::new(10_001), 5_000),
+            Uint128::new(5_001)
+        );
+        assert_eq!(
+            min_claimed(Uint128::new(20_000), 5_000),
+            Uint128::new(10_000)
+        );
+        assert_eq!(min_claimed(Uint128::MAX, 10_000), Uint128::MAX);
+        assert_eq!(min_claimed(Uint128::zero(), 1), Uint128::zero());
+    }
+
+    #[test]
+    fn migrate_escrows() {
+        use cosmwasm_std::testing::{mock_dependencies, mock_env};
+        use cosmwasm_std::Storage;
+
+        let mut deps = mock_dependencies();
+        let airdrop_addr = Addr::unchecked("airdrop");
+        let msg = MigrateMsg {
+            airdrop_code_ids: vec![1, 2],
+        };
+
+        // only the previous version of the contract is migrated
+        set_contract_version(&mut deps.storage, "crates.io:other", PREVIOUS_VERSION).unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::CannotMigrate { contract, .. } if contract == "crates.io:other"
+        ));
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, PREVIOUS_VERSION).unwrap();
+
+        let legacy_cfg = format!(
+            r#"{{"admin":"{}","release_addr":"{}","escrow_amount":"6","release_height_delta":"{}","allowed_native":"{}"}}"#,
+            ADMIN, RELEASE_ADDR, DEFAULT_RELEASE, NATIVE_DENOM
+        );
+        deps.storage.set(b"config", legacy_cfg.as_bytes());
+        let legacy_escrows: Map<(&Addr, u8), LegacyEscrow> = Map::new("escrow");
+        legacy_escrows
+            .save(
+                &mut deps.storage,
+                (&airdrop_addr, 1),
+                &LegacyEscrow {
+                    source: Addr::unchecked(USER),
+                    expiration: Expiration::AtHeight(20),
+                    escrow_amount: Some(Uint128::new(ESCROW_AMOUNT)),
+                    latest_stage: 1,
+                    released: false,
+                },
+            )
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap(),
+            Config {
+                admin: Addr::unchecked(ADMIN),
+                release_addr: Addr::unchecked(RELEASE_ADDR),
+                release_height_delta: Uint64::new(DEFAULT_RELEASE),
+                release_time_delta: None,
+                release_policy: ReleasePolicy::StageExpired {},
+                splits: ReleaseSplits::default(),
+            }
+        );
+        assert_eq!(
+            NATIVE_ASSETS.load(&deps.storage, NATIVE_DENOM).unwrap(),
+            Uint128::new(6)
+        );
+        assert_eq!(res.attributes[2].value, "1,2");
+        assert!(UNRELEASED_ESCROWS.has(&deps.storage, (&airdrop_addr, 1)));
+        assert!(AIRDROP_CODE_IDS.has(&deps.storage, 1));
+        assert!(AIRDROP_CODE_IDS.has(&deps.storage, 2));
+        let escrow = ESCROWS.load(&deps.storage, (&airdrop_addr, 1)).unwrap();
+        assert_eq!(
+            escrow,
+            Escrow {
+                source: Addr::unchecked(USER),
+                expiration: Expiration::AtHeight(20),
+                escrow_coin: Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: ESCROW_AMOUNT.into()
+                },
+                asset_type: AssetType::Native,
+                splits: ReleaseSplits::default(),
+                release_policy: ReleasePolicy::StageExpired {},
+                latest_stage: 1,
+                released: false,
+            }
+        );
+
+        // migrating again is refused and keeps the escrows
+        let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::CannotMigrate { version, .. } if version == CONTRACT_VERSION
+        ));
+        assert_eq!(
+            ESCROWS.load(&deps.storage, (&airdrop_addr, 1)).unwrap(),
+            escrow
+        );
+    }
+
+    mod cw20_escrow {
+        use super::*;
+        use cosmwasm_std::BlockInfo;
+        use cw20::{BalanceResponse, Cw20QueryMsg};
+
+        fn cw20_balance(app: &App, token_addr: &str, address: &str) -> Uint128 {
+            let res: BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    token_addr,
+                    &Cw20QueryMsg::Balance {
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            res.balance
+        }
+
+        #[test]
+        fn lock_and_release() {
+            let (mut app, cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            app.set_block(BlockInfo {
+                height: 1,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::AddCw20 {
+                token_addr: cw20_base_addr.clone(),
+                escrow_amount: Uint128::new(ESCROW_AMOUNT),
+            };
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+
+            // surplus is sent back
+            let msg = cw20::Cw20ExecuteMsg::Send {
+                contract: jt_controller_addr.clone(),
+                amount: Uint128::new(ESCROW_AMOUNT + 50),
+                msg: to_binary(&ReceiveMsg::LockFunds {
+                    airdrop_addr: cw20_airdrop_addr.clone(),
+                })
+                .unwrap(),
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&cw20_base_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                cw20_balance(&app, &cw20_base_addr, USER),
+                Uint128::new(1000000 - ESCROW_AMOUNT)
+            );
+
+            let msg = QueryMsg::Escrow {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+                stage: 1,
+            };
+            let res: EscrowResponse = app
+                .wrap()
+                .query_wasm_smart(&jt_controller_addr, &msg)
+                .unwrap();
+            assert_eq!(res.source, USER);
+            assert_eq!(res.asset_type, AssetType::Cw20);
+            assert_eq!(
+                res.escrow_coin,
+                Coin {
+                    denom: cw20_base_addr.clone(),
+                    amount: ESCROW_AMOUNT.into()
+                }
+            );
+
+            app.set_block(BlockInfo {
+                height: 150,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::ReleaseLockedFunds {
+                airdrop_addr: cw20_airdrop_addr,
+                stage: 1,
+            };
+            app.execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                cw20_balance(&app, &cw20_base_addr, RELEASE_ADDR),
+                Uint128::new(ESCROW_AMOUNT)
+            );
+            assert_eq!(
+                cw20_balance(&app, &cw20_base_addr, &jt_controller_addr),
+                Uint128::zero()
+            );
+        }
+
+        #[test]
+        fn unknown_token() {
+            let (mut app, cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            // only accepted token contracts can lock
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: USER.to_string(),
+                amount: Uint128::new(ESCROW_AMOUNT),
+                msg: to_binary(&ReceiveMsg::LockFunds {
+                    airdrop_addr: cw20_airdrop_addr,
+                })
+                .unwrap(),
+            });
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(cw20_base_addr),
+                    Addr::unchecked(jt_controller_addr),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast().unwrap(),
+                ContractError::Cw20NotAccepted {}
+            ));
+        }
+    }
+
+    #[test]
+    fn update_config() {
+        let (mut app, _cw20_base_addr, _cw20_airdrop_addr, jt_controller_addr) =
+            proper_instantiate();
+
+        let msg = ExecuteMsg::UpdateConfig {
+            admin: Some("new_admin".to_string()),
+            release_addr: Some("new_release".to_string()),
+            release_height_delta: Some(Uint64::new(69)),
+            release_time_delta: Some(Uint64::new(86400)),
+            release_policy: Some(ReleasePolicy::StageExpired {}),
+            splits: None,
+        };
+
+        let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
+            contract_addr: jt_controller_addr.clone(),
+            msg: to_binary(&msg).unwrap(),
+            funds: vec![],
+        });
+        // unauthorized
+        let err = app
+            .execute(Addr::unchecked(RANDOM), cosmos_msg.clone())
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::Unauthorized {}
+        ));
+
+        app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+        let res: ConfigResponse = app
+            .wrap()
+            .query_wasm_smart(jt_controller_addr, &QueryMsg::Config {})
+            .unwrap();
+
+        assert_eq!(res.admin, "new_admin");
+        assert_eq!(res.release_addr, "new_release");
+        assert_eq!(res.release_height_delta, Uint64::new(69));
+        assert_eq!(res.release_time_delta, Some(Uint64::new(86400)));
+        assert_eq!(res.release_policy, ReleasePolicy::StageExpired {});
+        assert_eq!(res.cw20_assets, vec![]);
+        assert_eq!(res.airdrop_code_ids.len(), 1);
+    }
+
+    #[test]
+    fn native_denoms() {
+        let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+            proper_instantiate();
+
+        let msg = ExecuteMsg::AddNativeDenom {
+            denom: OTHER_DENOM.to_string(),
+            escrow_amount: Uint128::new(40),
+        };
+        let err = app
+            .execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::Unauthorized {}
+        ));
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(&jt_controller_addr),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+        let res: ConfigResponse = app
+            .wrap()
+            .query_wasm_smart(&jt_controller_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(
+            res.native_assets,
+            vec![
+                Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(ESCROW_AMOUNT),
+                },
+                Coin {
+                    denom: OTHER_DENOM.to_string(),
+                    amount: Uint128::new(40),
+                },
+            ]
+        );
+
+        // any listed denom can pay the escrow
+        let msg = ExecuteMsg::LockFunds {
+            airdrop_addr: cw20_airdrop_addr.clone(),
+        };
+        app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(&jt_controller_addr),
+            &msg,
+            &[Coin {
+                denom: OTHER_DENOM.to_string(),
+                amount: Uint128::new(40),
+            }],
+        )
+        .unwrap();
+        let res: EscrowResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &jt_controller_addr,
+                &QueryMsg::Escrow {
+                    airdrop_addr: cw20_airdrop_addr,
+                    stage: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.escrow_coin,
+            Coin {
+                denom: OTHER_DENOM.to_string(),
+                amount: Uint128::new(40),
+            }
+        );
+
+        let msg = ExecuteMsg::RemoveNativeDenom {
+            denom: OTHER_DENOM.to_string(),
+        };
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(&jt_controller_addr),
+            &msg,
+            &[],
+        )
+        .unwrap();
+        let res: ConfigResponse = app
+            .wrap()
+            .query_wasm_smart(jt_controller_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(res.native_assets.len(), 1);
+    }
+
+    #[test]
+    fn release_expired() {
+        let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+            proper_instantiate();
+
+        app.set_block(BlockInfo {
+            height: 1,
+            time: Default::default(),
+            chain_id: "".to_string(),
+        });
+
+        let msg = ExecuteMsg::AddNativeDenom {
+            denom: NATIVE_DENOM.to_string(),
+            escrow_amount: Uint128::new(30),
+        };
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(&jt_controller_addr),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+        // lock three stages of the same airdrop
+        let lock_msg = ExecuteMsg::LockFunds {
+            airdrop_addr: cw20_airdrop_addr.clone(),
+        };
+        let funds = [Coin {
+            denom: NATIVE_DENOM.to_string(),
+            amount: Uint128::new(30),
+        }];
+        app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(&jt_controller_addr),
+            &lock_msg,
+            &funds,
+        )
+        .unwrap();
+        let register_msg = cw20_merkle_airdrop::msg::ExecuteMsg::RegisterMerkleRoot {
+            merkle_root: MERKLE_ROOT.to_string(),
+            expiration: None,
+            start: None,
+            total_amount: None,
+        };
+        app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(&cw20_airdrop_addr),
+            &register_msg,
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(&jt_controller_addr),
+            &lock_msg,
+            &funds,
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(&cw20_airdrop_addr),
+            &register_msg,
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            Addr::unchecked(&jt_controller_addr),
+            &lock_msg,
+            &funds,
+        )
+        .unwrap();
+
+        // nothing is expired yet
+        let msg = ExecuteMsg::ReleaseExpired {
+            start_after: None,
+            limit: None,
+        };
+        let res = app
+            .execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        let released = |res: &AppResponse| {
+            res.events
+                .iter()
+                .find(|e| e.ty == "wasm")
+                .unwrap()
+                .attributes
+                .iter()
+                .find(|a| a.key == "released")
+                .unwrap()
+                .value
+                .clone()
+        };
+        assert_eq!(released(&res), "0");
+
+        app.set_block(BlockInfo {
+            height: 150,
+            time: Default::default(),
+            chain_id: "".to_string(),
+        });
+
+        // each released escrow has its own event
+        let released_stages = |res: &AppResponse| -> Vec<String> {
+            res.events
+                .iter()
+                .filter(|e| e.ty == "wasm-release_escrow")
+                .map(|e| {
+                    e.attributes
+                        .iter()
+                        .find(|a| a.key == "stage")
+                        .unwrap()
+                        .value
+                        .clone()
+                })
+                .collect()
+        };
+
+        let last_scanned = |res: &AppResponse| {
+            res.events
+                .iter()
+                .find(|e| e.ty == "wasm")
+                .unwrap()
+                .attributes
+                .iter()
+                .find(|a| a.key == "last_scanned")
+                .map(|a| a.value.clone())
+        };
+        let transfers = |res: &AppResponse| -> Vec<String> {
+            res.events
+                .iter()
+                .filter(|e| e.ty == "transfer")
+                .map(|e| {
+                    e.attributes
+                        .iter()
+                        .find(|a| a.key == "amount")
+                        .unwrap()
+                        .value
+                        .clone()
+                })
+                .collect()
+        };
+
+        // the limit bounds the scan, the last scanned key is the next cursor
+        let msg = ExecuteMsg::ReleaseExpired {
+            start_after: None,
+            limit: Some(1),
+        };
+        let res = app
+            .execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(released(&res), "1");
+        assert_eq!(released_stages(&res), vec!["1"]);
+        assert_eq!(last_scanned(&res), Some(format!("{}:1", cw20_airdrop_addr)));
+        let balance = app
+            .wrap()
+            .query_balance(RELEASE_ADDR, NATIVE_DENOM)
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(30));
+
+        // two escrows paying the same recipient and denom are paid with a single send
+        let msg = ExecuteMsg::ReleaseExpired {
+            start_after: Some((cw20_airdrop_addr.clone(), 1)),
+            limit: Some(5),
+        };
+        let res = app
+            .execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(released(&res), "2");
+        assert_eq!(released_stages(&res), vec!["2", "3"]);
+        assert_eq!(transfers(&res), vec![format!("60{}", NATIVE_DENOM)]);
+        assert_eq!(last_scanned(&res), Some(format!("{}:3", cw20_airdrop_addr)));
+        let balance = app
+            .wrap()
+            .query_balance(RELEASE_ADDR, NATIVE_DENOM)
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(90));
+
+        // nothing left to scan
+        let msg = ExecuteMsg::ReleaseExpired {
+            start_after: None,
+            limit: None,
+        };
+        let res = app
+            .execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(released(&res), "0");
+        assert_eq!(last_scanned(&res), None);
+
+        // released escrows are not listed nor released again
+        let res: ListEscrowsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &jt_controller_addr,
+                &QueryMsg::ListExpiredEscrows {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.escrows.len(), 0);
+        let msg = ExecuteMsg::ReleaseLockedFunds {
+            airdrop_addr: cw20_airdrop_addr,
+            stage: 2,
+        };
+        let err = app
+            .execute_contract(
+                Addr::unchecked(RANDOM),
+                Addr::unchecked(jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast().unwrap(),
+            ContractError::EscrowAlreadyReleased {}
+        ));
+    }
+
+    mod queries {
+        use super::*;
+        use cosmwasm_std::BlockInfo;
+
+        #[test]
+        fn query_expired_escrows() {
+            let (mut app, _cw20_base_addr, _cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+    
+            app.set_block(BlockInfo {
+                height: 1,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+    
+            // cannot send without tokens
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: _cw20_airdrop_addr.clone(),
+            };
+            let cosmos_msg = CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: jt_controller_addr.clone(),
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(ESCROW_AMOUNT),
+                }],
+            });
+            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+    
+            let msg = QueryMsg::ListExpiredEscrows { start_after: None, limit: None };
+            let res: ListEscrowsResponse = app
+            .wrap()
+            .query_wasm_smart(&jt_controller_addr, &msg)
+            .unwrap();
+            assert_eq!(res.escrows.len(), 0);
+
+            app.set_block(BlockInfo {
+                height: 150,
+                time: Default::default(),
+                chain_id: "".to_string(),
+            });
+
+            let msg = QueryMsg::ListExpiredEscrows { start_after: None, limit: None };
             let res: ListEscrowsResponse = app
             .wrap()
             .query_wasm_smart(jt_controller_addr, &msg)
@@ -779,5 +2747,89 @@ mod tests {
             assert_eq!(res.escrows.len(), 1);
             assert_eq!(res.escrows[0].released, false);
         }
+
+        #[test]
+        fn query_time_expired_escrows() {
+            use cosmwasm_std::Timestamp;
+
+            let (mut app, _cw20_base_addr, cw20_airdrop_addr, jt_controller_addr) =
+                proper_instantiate();
+
+            app.set_block(BlockInfo {
+                height: 1,
+                time: Timestamp::from_seconds(1000),
+                chain_id: "".to_string(),
+            });
+
+            let msg = ExecuteMsg::UpdateConfig {
+                admin: None,
+                release_addr: None,
+                release_height_delta: None,
+                release_time_delta: Some(Uint64::new(86400)),
+                release_policy: None,
+                splits: None,
+            };
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[],
+            )
+            .unwrap();
+
+            let msg = ExecuteMsg::LockFunds {
+                airdrop_addr: cw20_airdrop_addr.clone(),
+            };
+            app.execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked(&jt_controller_addr),
+                &msg,
+                &[Coin {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(ESCROW_AMOUNT),
+                }],
+            )
+            .unwrap();
+
+            let msg = QueryMsg::Escrow {
+                airdrop_addr: cw20_airdrop_addr,
+                stage: 1,
+            };
+            let res: EscrowResponse = app
+                .wrap()
+                .query_wasm_smart(&jt_controller_addr, &msg)
+                .unwrap();
+            assert_eq!(
+                res.expiration,
+                Expiration::AtTime(Timestamp::from_seconds(1000 + 86400))
+            );
+
+            // the height passing does not expire the escrow
+            app.set_block(BlockInfo {
+                height: 150,
+                time: Timestamp::from_seconds(2000),
+                chain_id: "".to_string(),
+            });
+            let msg = QueryMsg::ListExpiredEscrows {
+                start_after: None,
+                limit: None,
+            };
+            let res: ListEscrowsResponse = app
+                .wrap()
+                .query_wasm_smart(&jt_controller_addr, &msg)
+                .unwrap();
+            assert_eq!(res.escrows.len(), 0);
+
+            app.set_block(BlockInfo {
+                height: 151,
+                time: Timestamp::from_seconds(1000 + 86400),
+                chain_id: "".to_string(),
+            });
+            let res: ListEscrowsResponse = app
+                .wrap()
+                .query_wasm_smart(jt_controller_addr, &msg)
+                .unwrap();
+            assert_eq!(res.escrows.len(), 1);
+        }
     }
 }
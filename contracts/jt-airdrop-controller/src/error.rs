@@ -23,6 +23,24 @@ pub enum ContractError {
 
     #[error("Cannot release funds")]
     CannotReleaseFunds {},
+
+    #[error("Cw20 token is not accepted for escrows")]
+    Cw20NotAccepted {},
+
+    #[error("No merkle root registered for stage {stage}")]
+    NoMerkleRoot { stage: u8 },
+
+    #[error("Airdrop code id {code_id} is not approved")]
+    CodeIdNotApproved { code_id: u64 },
+
+    #[error("Invalid release policy")]
+    InvalidReleasePolicy {},
+
+    #[error("Splits can not exceed 10000 basis points")]
+    InvalidSplits {},
+
+    #[error("Cannot migrate from {contract} version {version}")]
+    CannotMigrate { contract: String, version: String },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
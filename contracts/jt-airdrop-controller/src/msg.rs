@@ -1,18 +1,30 @@
-use cosmwasm_std::{Addr, Uint128, Uint64};
+use cosmwasm_std::{Addr, Coin, Uint128, Uint64};
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
 use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::state::{AssetType, ReleasePolicy, ReleaseSplits};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     /// Admin if none set to sender
     pub admin: Option<String>,
     /// ReleaseAddr if not set, set to admin
     pub release_addr: Option<String>,
-    pub escrow_amount: Uint128,
-    pub allowed_native: String,
+    /// accepted native denoms with their escrow amounts
+    pub native_assets: Vec<Coin>,
+    /// code ids of approved airdrop contracts
+    pub airdrop_code_ids: Vec<u64>,
     /// release_height_delta gets added to the current block height
     pub release_height_delta: Uint64,
+    /// release_time_delta in seconds gets added to the current block time,
+    /// escrows expire at time instead of height when it is set
+    pub release_time_delta: Option<Uint64>,
+    /// defaults to StageExpired
+    pub release_policy: Option<ReleasePolicy>,
+    /// defaults to releasing the whole escrow to a single recipient
+    pub splits: Option<ReleaseSplits>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,17 +33,66 @@ pub enum ExecuteMsg {
     UpdateConfig {
         admin: Option<String>,
         release_addr: Option<String>,
-        escrow_amount: Option<Uint128>,
         release_height_delta: Option<Uint64>,
-        allowed_native: Option<String>,
+        /// zero switches escrows back to expire at height
+        release_time_delta: Option<Uint64>,
+        release_policy: Option<ReleasePolicy>,
+        splits: Option<ReleaseSplits>,
     },
     ReleaseLockedFunds {
         airdrop_addr: String,
         stage: u8,
     },
+    /// Scans up to limit unreleased escrows after the (airdrop_addr, stage) start_after and
+    /// releases the expired ones, paying each recipient once per denom. The last scanned key
+    /// is returned in the last_scanned attribute
+    ReleaseExpired {
+        start_after: Option<(String, u8)>,
+        limit: Option<u32>,
+    },
     LockFunds {
         airdrop_addr: String,
     },
+    /// Locks an escrow in an accepted cw20 token
+    Receive(Cw20ReceiveMsg),
+    /// Accepts escrows in a cw20 token, or updates its escrow amount
+    AddCw20 {
+        token_addr: String,
+        escrow_amount: Uint128,
+    },
+    RemoveCw20 {
+        token_addr: String,
+    },
+    /// Accepts escrows in a native denom, or updates its escrow amount
+    AddNativeDenom {
+        denom: String,
+        escrow_amount: Uint128,
+    },
+    RemoveNativeDenom {
+        denom: String,
+    },
+    /// Approves escrows for airdrop contracts instantiated from the code id
+    AddAirdropCodeId {
+        code_id: u64,
+    },
+    RemoveAirdropCodeId {
+        code_id: u64,
+    },
+    /// Lets the delegate lock escrows for the airdrop, only callable by the airdrop owner
+    AddDelegate {
+        airdrop_addr: String,
+        delegate: String,
+    },
+    RemoveDelegate {
+        airdrop_addr: String,
+        delegate: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    LockFunds { airdrop_addr: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -50,6 +111,17 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    ListDelegates {
+        airdrop_addr: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    /// code ids of approved airdrop contracts, escrows can not be locked before any is approved
+    pub airdrop_code_ids: Vec<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -57,17 +129,28 @@ pub struct ConfigResponse {
     pub admin: Addr,
     /// ReleaseAddr is the address of the released unproven airdrops
     pub release_addr: Addr,
-    pub escrow_amount: Uint128,
     /// release height is current_height + default_heighjt
     pub release_height_delta: Uint64,
-    pub allowed_native: String,
+    /// release time is current_time + release_time_delta, replaces the release height when set
+    pub release_time_delta: Option<Uint64>,
+    pub release_policy: ReleasePolicy,
+    pub splits: ReleaseSplits,
+    /// accepted native denoms with their escrow amounts
+    pub native_assets: Vec<Coin>,
+    /// accepted cw20 tokens with their escrow amounts
+    pub cw20_assets: Vec<Cw20Coin>,
+    /// code ids of approved airdrop contracts
+    pub airdrop_code_ids: Vec<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct EscrowResponse {
     pub source: String,
     pub expiration: Expiration,
-    pub escrow_amount: Uint128,
+    pub escrow_coin: Coin,
+    pub asset_type: AssetType,
+    pub splits: ReleaseSplits,
+    pub release_policy: ReleasePolicy,
     pub latest_stage: u8,
     pub released: bool,
 }
@@ -76,3 +159,8 @@ pub struct EscrowResponse {
 pub struct ListEscrowsResponse {
     pub escrows: Vec<EscrowResponse>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListDelegatesResponse {
+    pub delegates: Vec<String>,
+}
@@ -1,19 +1,57 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128, Uint64};
+use cosmwasm_std::{Addr, Coin, Empty, Uint128, Uint64};
 use cw_storage_plus::{Item, Map};
-use cw_utils::Expiration;
+use cw_utils::{Duration, Expiration};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub admin: Addr,
     /// ReleaseAddr is the address of the released unproven airdrops
     pub release_addr: Addr,
-    pub escrow_amount: Uint128,
     /// release_height_delta gets added to the current block height
     pub release_height_delta: Uint64,
-    pub allowed_native: String,
+    /// release_time_delta in seconds gets added to the current block time,
+    /// escrows expire at time instead of height when it is set
+    #[serde(default)]
+    pub release_time_delta: Option<Uint64>,
+    #[serde(default)]
+    pub release_policy: ReleasePolicy,
+    #[serde(default)]
+    pub splits: ReleaseSplits,
+}
+
+/// ReleaseSplits divide a released escrow between its source and the release address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReleaseSplits {
+    /// basis points of an expired escrow returned to the source, the rest goes to release_addr
+    pub expired_source_bps: u16,
+    /// basis points of a successful escrow paid to release_addr, the rest goes to the source
+    pub success_fee_bps: u16,
+}
+
+/// ReleasePolicy decides when the source can take back an escrow before it expires
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleasePolicy {
+    /// a stage after the escrowed one is registered
+    StageIncreased {},
+    /// at least min_claimed_bps basis points of the escrowed stage total_amount are claimed
+    ClaimedRatio { min_claimed_bps: u16 },
+    /// the duration passed since the start of the escrowed stage, stages without a start,
+    /// or with a start at height for a time duration and the other way around, never meet it
+    MinDurationSinceStart { duration: Duration },
+    /// the escrowed stage expired
+    StageExpired {},
+}
+
+/// configs stored before release policies existed get the strictest policy, a source can not
+/// register an empty stage to take back its escrow
+impl Default for ReleasePolicy {
+    fn default() -> Self {
+        ReleasePolicy::StageExpired {}
+    }
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -23,10 +61,43 @@ pub struct Escrow {
     /// if refunded, funds go to the source
     pub source: Addr,
     pub expiration: Expiration,
-    pub escrow_amount: Uint128,
+    /// coin locked at creation, config changes do not affect it.
+    /// For cw20 escrows the denom is the token contract address
+    pub escrow_coin: Coin,
+    #[serde(default)]
+    pub asset_type: AssetType,
+    /// splits at creation, config changes do not affect them
+    #[serde(default)]
+    pub splits: ReleaseSplits,
+    /// release policy at creation, config changes do not affect it
+    #[serde(default)]
+    pub release_policy: ReleasePolicy,
     pub latest_stage: u8,
     pub released: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetType {
+    #[default]
+    Native,
+    Cw20,
+}
+
 /// ESCROWS: index (airdrop_addr, stage) -> Escrow
 pub const ESCROWS: Map<(&Addr, u8), Escrow> = Map::new("escrow");
+
+/// UNRELEASED_ESCROWS: keys of ESCROWS not released yet, batch releases skip released history
+pub const UNRELEASED_ESCROWS: Map<(&Addr, u8), Empty> = Map::new("unreleased_escrows");
+
+/// DELEGATES: (airdrop_addr, delegate) allowed to lock escrows besides the airdrop owner
+pub const DELEGATES: Map<(&Addr, &Addr), Empty> = Map::new("delegates");
+
+/// AIRDROP_CODE_IDS: code ids of approved airdrop contracts
+pub const AIRDROP_CODE_IDS: Map<u64, Empty> = Map::new("airdrop_code_ids");
+
+/// NATIVE_ASSETS: accepted native denom -> escrow amount
+pub const NATIVE_ASSETS: Map<&str, Uint128> = Map::new("native_assets");
+
+/// CW20_ASSETS: accepted cw20 token contract -> escrow amount
+pub const CW20_ASSETS: Map<&Addr, Uint128> = Map::new("cw20_assets");